@@ -13,6 +13,46 @@ bool onod_run(const char* test,
               size_t len,
               double* result /* length >= 3 */);
 
+// SP 800-90B continuous health tests. The states live in caller memory; treat their fields as private.
+typedef struct {
+    uint64_t alarms;
+    uint32_t cutoff;
+    uint32_t count;
+    uint8_t last;
+    bool started;
+} OnodRct;
+
+typedef struct {
+    uint64_t alarms;
+    uint32_t window;
+    uint32_t cutoff;
+    uint32_t seen;
+    uint32_t count;
+    uint8_t reference;
+} OnodApt;
+
+// Repetition Count Test for min-entropy h per sample and false positive probability alpha (e.g. 2^-20).
+// Returns false for invalid parameters.
+bool onod_rct_init(OnodRct* rct, double h, double alpha);
+// Returns true when the sample raised an alarm.
+bool onod_rct_push(OnodRct* rct, uint8_t sample);
+// Returns the number of alarms raised by the samples.
+uint64_t onod_rct_process(OnodRct* rct, const uint8_t* samples, size_t len);
+uint32_t onod_rct_cutoff(const OnodRct* rct);
+uint64_t onod_rct_alarms(const OnodRct* rct);
+void onod_rct_reset(OnodRct* rct);
+
+// Adaptive Proportion Test; window is 512 for binary and 1024 for non-binary samples in SP 800-90B.
+// Returns false for invalid parameters.
+bool onod_apt_init(OnodApt* apt, double h, double alpha, uint32_t window);
+// Returns true when the sample raised an alarm.
+bool onod_apt_push(OnodApt* apt, uint8_t sample);
+// Returns the number of alarms raised by the samples.
+uint64_t onod_apt_process(OnodApt* apt, const uint8_t* samples, size_t len);
+uint32_t onod_apt_cutoff(const OnodApt* apt);
+uint64_t onod_apt_alarms(const OnodApt* apt);
+void onod_apt_reset(OnodApt* apt);
+
 #ifdef __cplusplus
 }
 #endif
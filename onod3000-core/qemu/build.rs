@@ -0,0 +1,58 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+// Places the linker script and computes the results the target has to reproduce with the host build of the core.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use onod3000_core::special::{normal_p_value, upper_gamma};
+use onod3000_core::uniformity::{chi_bit, chi_byte, mean_byte, monobit, prediction, run_ups};
+
+#[path = "src/inputs.rs"]
+mod inputs;
+
+fn main() {
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::copy("memory.x", out.join("memory.x")).unwrap();
+    println!("cargo:rustc-link-search={}", out.display());
+    println!("cargo:rerun-if-changed=memory.x");
+    println!("cargo:rerun-if-changed=src/inputs.rs");
+
+    let (random, biased, short) = inputs::inputs();
+    let mut expected = String::from("const UNIFORMITY: [[(f64, f64, f64); 3]; 6] = [\n");
+    for test in [monobit, chi_bit, chi_byte, mean_byte, run_ups, prediction] {
+        let results = [test(&random), test(&biased), test(&short)];
+        writeln!(expected, "    {:?},", results).unwrap();
+    }
+    expected.push_str("];\n");
+    let gamma = inputs::GAMMA_POINTS.map(|(a, x)| upper_gamma(a, x));
+    writeln!(expected, "const UPPER_GAMMA: [f64; {}] = {:?};", gamma.len(), gamma).unwrap();
+    let normal = inputs::Z_SCORES.map(normal_p_value);
+    writeln!(expected, "const NORMAL_P: [f64; {}] = {:?};", normal.len(), normal).unwrap();
+    fs::write(out.join("expected.rs"), expected).unwrap();
+}
@@ -0,0 +1,6 @@
+/* MPS2 AN386: 4 MB of code SRAM at 0 and 4 MB of data SRAM at 0x20000000 */
+MEMORY
+{
+  FLASH : ORIGIN = 0x00000000, LENGTH = 4M
+  RAM : ORIGIN = 0x20000000, LENGTH = 4M
+}
@@ -0,0 +1,49 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+// Inputs shared by the build script, which computes the expected results on the host, and the target.
+
+/// Pseudorandom bytes from xorshift32, optionally with the top bit forced to bias them.
+pub fn samples<const N: usize>(seed: u32, bias: u8) -> [u8; N] {
+    let mut state = seed;
+    core::array::from_fn(|_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        (state >> 24) as u8 | bias
+    })
+}
+
+/// Inputs of the uniformity tests: random, biased and short.
+pub fn inputs() -> ([u8; 100_000], [u8; 4_000], [u8; 40]) {
+    (samples(1, 0), samples(2, 0x80), samples(3, 0))
+}
+
+/// Points at which the special functions are evaluated, as (a, x) of Q(a, x).
+pub const GAMMA_POINTS: [(f64, f64); 8] =
+    [(0.5, 0.01), (0.5, 2.0), (1.5, 0.5), (4.5, 5.5), (4.5, 50.0), (127.5, 2.0), (127.5, 127.5), (127.5, 200.0)];
+
+/// z-scores at which the normal p-value is evaluated.
+pub const Z_SCORES: [f64; 6] = [-6.0, -1.0, 0.0, 0.3, 1.96, 8.0];
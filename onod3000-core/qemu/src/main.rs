@@ -0,0 +1,122 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+// Runs the core on a Cortex-M4F under QEMU and checks it against the host: Cortex-M4F has no double precision
+// FPU, so every f64 operation goes through the compiler's soft-float routines there.
+
+#![no_std]
+#![no_main]
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::debug::{self, EXIT_FAILURE, EXIT_SUCCESS};
+use cortex_m_semihosting::hprintln;
+use libm::fabs;
+use onod3000_core::health::{AdaptiveProportion, RepetitionCount, DEFAULT_ALPHA, WINDOW};
+use onod3000_core::special::{normal_p_value, upper_gamma};
+use onod3000_core::uniformity::*;
+use panic_semihosting as _;
+
+mod inputs;
+
+include!(concat!(env!("OUT_DIR"), "/expected.rs"));
+
+/// Whether `value` is within 1e-9 of `expected`, relative to |expected| when that exceeds 1, as in the host tests.
+fn close(value: f64, expected: f64) -> bool {
+    fabs(value - expected) <= 1e-9 * fabs(expected).max(1.0)
+}
+
+/// Prints the outcome of a check and returns whether it passed.
+fn check(name: &str, passed: bool) -> bool {
+    hprintln!("{} ... {}", name, if passed { "ok" } else { "FAILED" });
+    passed
+}
+
+fn uniformity() -> bool {
+    let names = ["monobit", "chi_bit", "chi_byte", "mean_byte", "run_ups", "prediction"];
+    let tests = [monobit, chi_bit, chi_byte, mean_byte, run_ups, prediction];
+    let (random, biased, short) = inputs::inputs();
+    let mut passed = true;
+    for ((name, test), expected) in names.into_iter().zip(tests).zip(UNIFORMITY) {
+        let matches = [&random[..], &biased[..], &short[..]].into_iter().zip(expected).all(|(input, expected)| {
+            let result = test(input);
+            close(result.0, expected.0) && close(result.1, expected.1) && close(result.2, expected.2)
+        });
+        passed &= check(name, matches);
+    }
+
+    let mut streaming = (Monobit::default(), RunUps::default(), Prediction::default());
+    for chunk in random.chunks(333) {
+        streaming.0.update(chunk);
+        streaming.1.update(chunk);
+        streaming.2.update(chunk);
+    }
+    passed & check(
+        "streaming matches one-shot",
+        streaming.0.finish() == monobit(&random)
+            && streaming.1.finish() == run_ups(&random)
+            && streaming.2.finish() == prediction(&random),
+    )
+}
+
+fn special() -> bool {
+    let gamma =
+        inputs::GAMMA_POINTS.iter().zip(UPPER_GAMMA).all(|(&(a, x), expected)| close(upper_gamma(a, x), expected));
+    let normal = inputs::Z_SCORES.iter().zip(NORMAL_P).all(|(&z, expected)| close(normal_p_value(z), expected));
+    check("upper_gamma", gamma) & check("normal_p_value", normal)
+}
+
+fn health() -> bool {
+    // Cutoffs of SP 800-90B section 4.4, as in the host tests
+    let rct = [(0.5, 41), (1.0, 21), (2.0, 11), (4.0, 6), (8.0, 4)]
+        .iter()
+        .all(|&(h, cutoff)| RepetitionCount::new(h, DEFAULT_ALPHA).cutoff() == cutoff);
+    let apt = [(0.5, 410), (1.0, 311)]
+        .iter()
+        .all(|&(h, cutoff)| AdaptiveProportion::new(h, DEFAULT_ALPHA, true).cutoff() == cutoff)
+        && [(0.5, 793), (1.0, 589), (2.0, 325), (4.0, 105), (8.0, 18)]
+            .iter()
+            .all(|&(h, cutoff)| AdaptiveProportion::new(h, DEFAULT_ALPHA, false).cutoff() == cutoff);
+
+    let mut stuck = (RepetitionCount::new(1.0, DEFAULT_ALPHA), AdaptiveProportion::new(8.0, DEFAULT_ALPHA, false));
+    let alarms = stuck.0.process(&[0x5a; 20]) == 0
+        && stuck.0.push(0x5a)
+        && stuck.1.process(&[0x5a; 17]) == 0
+        && stuck.1.push(0x5a)
+        && stuck.1.process(&[0x5a; 2 * WINDOW as usize - 18]) == 1
+        && stuck.1.alarms() == 2;
+
+    check("repetition count cutoffs", rct)
+        & check("adaptive proportion cutoffs", apt)
+        & check("stuck source raises alarms", alarms)
+}
+
+#[entry]
+fn main() -> ! {
+    let passed = uniformity() & special() & health();
+    debug::exit(if passed { EXIT_SUCCESS } else { EXIT_FAILURE });
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
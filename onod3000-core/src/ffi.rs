@@ -0,0 +1,148 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+// C interface of the health tests, shared by firmware and the host library. The states are `#[repr(C)]` and
+// live in caller memory, declared in include/onod3000.h as `OnodRct` and `OnodApt`.
+
+use crate::health::{AdaptiveProportion, RepetitionCount};
+
+/// Initializes the repetition count test state at `rct` for samples of `h` bits of min-entropy and a false
+/// positive probability of `alpha`. Returns false, leaving `rct` untouched, for invalid parameters.
+///
+/// # Safety
+/// `rct` must point to writable space for an `OnodRct`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_rct_init(rct: *mut RepetitionCount, h: f64, alpha: f64) -> bool {
+    if rct.is_null() || !(h > 0.0 && h <= 8.0) || !(alpha > 0.0 && alpha < 1.0) {
+        return false;
+    }
+    unsafe { rct.write(RepetitionCount::new(h, alpha)) };
+    true
+}
+
+/// Processes `len` samples and returns the number of alarms they raised.
+///
+/// # Safety
+/// `rct` must point to a state initialized by `onod_rct_init` and `samples` to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn onod_rct_process(rct: *mut RepetitionCount, samples: *const u8, len: usize) -> u64 {
+    if rct.is_null() || (samples.is_null() && len > 0) {
+        return 0;
+    }
+    let samples = if len == 0 { &[][..] } else { unsafe { core::slice::from_raw_parts(samples, len) } };
+    unsafe { (*rct).process(samples) }
+}
+
+/// Processes one sample and returns whether it raised an alarm.
+///
+/// # Safety
+/// `rct` must point to a state initialized by `onod_rct_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_rct_push(rct: *mut RepetitionCount, sample: u8) -> bool {
+    !rct.is_null() && unsafe { (*rct).push(sample) }
+}
+
+/// # Safety
+/// `rct` must point to a state initialized by `onod_rct_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_rct_cutoff(rct: *const RepetitionCount) -> u32 {
+    if rct.is_null() { 0 } else { unsafe { (*rct).cutoff() } }
+}
+
+/// # Safety
+/// `rct` must point to a state initialized by `onod_rct_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_rct_alarms(rct: *const RepetitionCount) -> u64 {
+    if rct.is_null() { 0 } else { unsafe { (*rct).alarms() } }
+}
+
+/// # Safety
+/// `rct` must point to a state initialized by `onod_rct_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_rct_reset(rct: *mut RepetitionCount) {
+    if !rct.is_null() {
+        unsafe { (*rct).reset() }
+    }
+}
+
+/// Initializes the adaptive proportion test state at `apt` for samples of `h` bits of min-entropy, a false
+/// positive probability of `alpha` and a window of `window` samples (512 for binary and 1024 for other samples
+/// in SP 800-90B). Returns false, leaving `apt` untouched, for invalid parameters.
+///
+/// # Safety
+/// `apt` must point to writable space for an `OnodApt`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_apt_init(apt: *mut AdaptiveProportion, h: f64, alpha: f64, window: u32) -> bool {
+    if apt.is_null() || !(h > 0.0 && h <= 8.0) || !(alpha > 0.0 && alpha < 1.0) || window == 0 {
+        return false;
+    }
+    unsafe { apt.write(AdaptiveProportion::with_window(h, alpha, window)) };
+    true
+}
+
+/// Processes `len` samples and returns the number of alarms they raised.
+///
+/// # Safety
+/// `apt` must point to a state initialized by `onod_apt_init` and `samples` to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn onod_apt_process(apt: *mut AdaptiveProportion, samples: *const u8, len: usize) -> u64 {
+    if apt.is_null() || (samples.is_null() && len > 0) {
+        return 0;
+    }
+    let samples = if len == 0 { &[][..] } else { unsafe { core::slice::from_raw_parts(samples, len) } };
+    unsafe { (*apt).process(samples) }
+}
+
+/// Processes one sample and returns whether it raised an alarm.
+///
+/// # Safety
+/// `apt` must point to a state initialized by `onod_apt_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_apt_push(apt: *mut AdaptiveProportion, sample: u8) -> bool {
+    !apt.is_null() && unsafe { (*apt).push(sample) }
+}
+
+/// # Safety
+/// `apt` must point to a state initialized by `onod_apt_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_apt_cutoff(apt: *const AdaptiveProportion) -> u32 {
+    if apt.is_null() { 0 } else { unsafe { (*apt).cutoff() } }
+}
+
+/// # Safety
+/// `apt` must point to a state initialized by `onod_apt_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_apt_alarms(apt: *const AdaptiveProportion) -> u64 {
+    if apt.is_null() { 0 } else { unsafe { (*apt).alarms() } }
+}
+
+/// # Safety
+/// `apt` must point to a state initialized by `onod_apt_init`.
+#[no_mangle]
+pub unsafe extern "C" fn onod_apt_reset(apt: *mut AdaptiveProportion) {
+    if !apt.is_null() {
+        unsafe { (*apt).reset() }
+    }
+}
@@ -0,0 +1,227 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use libm::{ceil, exp, log, log2, pow};
+
+/// False positive probability SP 800-90B recommends as the upper limit for health tests, 2^-20.
+pub const DEFAULT_ALPHA: f64 = 1.0 / 1_048_576.0;
+
+/// Window of the adaptive proportion test for binary samples.
+pub const BINARY_WINDOW: u32 = 512;
+
+/// Window of the adaptive proportion test for non-binary samples.
+pub const WINDOW: u32 = 1024;
+
+fn check(h: f64, alpha: f64) {
+    assert!(h > 0.0 && h <= 8.0, "The min-entropy per sample must be in (0, 8].");
+    assert!(alpha > 0.0 && alpha < 1.0, "The false positive probability must be in (0, 1).");
+}
+
+/// Repetition count test (SP 800-90B, 4.4.1): raises an alarm when a sample repeats C times in a row, for the
+/// cutoff C = 1 + ceil(-log2(alpha) / H).
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepetitionCount {
+    alarms: u64,
+    cutoff: u32,
+    count: u32,
+    last: u8,
+    started: bool,
+}
+
+impl RepetitionCount {
+    /// Repetition count test for samples of `h` bits of min-entropy at a false positive probability of `alpha`.
+    pub fn new(h: f64, alpha: f64) -> Self {
+        check(h, alpha);
+        let cutoff = 1 + ceil(-log2(alpha) / h) as u32;
+        RepetitionCount { alarms: 0, cutoff, count: 0, last: 0, started: false }
+    }
+
+    pub fn cutoff(&self) -> u32 {
+        self.cutoff
+    }
+
+    /// Number of alarms raised so far.
+    pub fn alarms(&self) -> u64 {
+        self.alarms
+    }
+
+    /// Processes one sample and returns whether it completed a run of `cutoff` identical samples.
+    pub fn push(&mut self, sample: u8) -> bool {
+        if self.started && sample == self.last {
+            self.count = self.count.saturating_add(1);
+        } else {
+            self.last = sample;
+            self.count = 1;
+            self.started = true;
+        }
+        let alarm = self.count == self.cutoff;
+        self.alarms += alarm as u64;
+        alarm
+    }
+
+    /// Processes `samples` and returns the number of alarms they raised.
+    pub fn process(&mut self, samples: &[u8]) -> u64 {
+        samples.iter().map(|&sample| self.push(sample) as u64).sum()
+    }
+
+    /// Forgets the current run and the alarms, keeping the cutoff.
+    pub fn reset(&mut self) {
+        *self = RepetitionCount { alarms: 0, cutoff: self.cutoff, count: 0, last: 0, started: false };
+    }
+}
+
+/// Adaptive proportion test (SP 800-90B, 4.4.2): raises an alarm when the first sample of a window recurs C
+/// times in it, for the cutoff C = 1 + CRITBINOM(W, 2^-H, 1 - alpha).
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveProportion {
+    alarms: u64,
+    window: u32,
+    cutoff: u32,
+    seen: u32,
+    count: u32,
+    reference: u8,
+}
+
+impl AdaptiveProportion {
+    /// Adaptive proportion test for samples of `h` bits of min-entropy at a false positive probability of
+    /// `alpha`, with the standard window of 512 samples for binary and 1024 for other samples.
+    pub fn new(h: f64, alpha: f64, binary: bool) -> Self {
+        Self::with_window(h, alpha, if binary { BINARY_WINDOW } else { WINDOW })
+    }
+
+    pub fn with_window(h: f64, alpha: f64, window: u32) -> Self {
+        check(h, alpha);
+        assert!(window > 0, "The window must hold at least one sample.");
+        let cutoff = 1 + critical_binomial(window, pow(2.0, -h), alpha);
+        AdaptiveProportion { alarms: 0, window, cutoff, seen: 0, count: 0, reference: 0 }
+    }
+
+    pub fn window(&self) -> u32 {
+        self.window
+    }
+
+    pub fn cutoff(&self) -> u32 {
+        self.cutoff
+    }
+
+    /// Number of alarms raised so far.
+    pub fn alarms(&self) -> u64 {
+        self.alarms
+    }
+
+    /// Processes one sample and returns whether the first sample of the current window has now occurred
+    /// `cutoff` times in it.
+    pub fn push(&mut self, sample: u8) -> bool {
+        if self.seen == 0 {
+            self.reference = sample;
+            self.count = 0;
+        }
+        self.count += (sample == self.reference) as u32;
+        self.seen += 1;
+        if self.seen == self.window {
+            self.seen = 0;
+        }
+        let alarm = sample == self.reference && self.count == self.cutoff;
+        self.alarms += alarm as u64;
+        alarm
+    }
+
+    /// Processes `samples` and returns the number of alarms they raised.
+    pub fn process(&mut self, samples: &[u8]) -> u64 {
+        samples.iter().map(|&sample| self.push(sample) as u64).sum()
+    }
+
+    /// Starts a new window and forgets the alarms, keeping the cutoff.
+    pub fn reset(&mut self) {
+        *self = AdaptiveProportion { alarms: 0, seen: 0, count: 0, reference: 0, ..*self };
+    }
+}
+
+/// CRITBINOM(n, p, 1 - alpha): the smallest k for which a binomial(n, p) variable exceeds k with probability
+/// at most `alpha`.
+pub fn critical_binomial(n: u32, p: f64, alpha: f64) -> u32 {
+    // Summing the upper tail downwards from k = n, with log binomial coefficients to keep p^k from underflowing
+    let (ln_p, ln_q) = (log(p), log(1.0 - p));
+    let mut ln_choose = 0.0;
+    let mut tail = 0.0;
+    for k in (0..=n).rev() {
+        let probability = exp(ln_choose + k as f64 * ln_p + (n - k) as f64 * ln_q);
+        if tail + probability > alpha {
+            return k;
+        }
+        tail += probability;
+        ln_choose += log(k as f64 / (n - k + 1) as f64);
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repetition_count_cutoffs() {
+        for (h, cutoff) in [(0.5, 41), (1.0, 21), (2.0, 11), (4.0, 6), (8.0, 4)] {
+            assert_eq!(RepetitionCount::new(h, DEFAULT_ALPHA).cutoff(), cutoff, "H = {}", h);
+        }
+    }
+
+    #[test]
+    fn adaptive_proportion_cutoffs() {
+        // Table 2 of SP 800-90B section 4.4.2
+        for (h, cutoff) in [(0.5, 410), (1.0, 311)] {
+            assert_eq!(AdaptiveProportion::new(h, DEFAULT_ALPHA, true).cutoff(), cutoff, "H = {}", h);
+        }
+        for (h, cutoff) in [(0.5, 793), (1.0, 589), (2.0, 325), (4.0, 105), (8.0, 18)] {
+            assert_eq!(AdaptiveProportion::new(h, DEFAULT_ALPHA, false).cutoff(), cutoff, "H = {}", h);
+        }
+    }
+
+    #[test]
+    fn stuck_source_raises_alarms() {
+        let mut rct = RepetitionCount::new(1.0, DEFAULT_ALPHA);
+        assert_eq!(rct.process(&[0x5a; 20]), 0);
+        assert!(rct.push(0x5a));
+
+        let mut apt = AdaptiveProportion::new(8.0, DEFAULT_ALPHA, false);
+        assert_eq!(apt.process(&[0x5a; 17]), 0);
+        assert!(apt.push(0x5a));
+        assert_eq!(apt.process(&[0x5a; 2 * WINDOW as usize - 18]), 1);
+        assert_eq!(apt.alarms(), 2);
+    }
+
+    #[test]
+    fn varying_source_raises_no_alarms() {
+        let mut rct = RepetitionCount::new(8.0, DEFAULT_ALPHA);
+        let mut apt = AdaptiveProportion::new(8.0, DEFAULT_ALPHA, false);
+        for _ in 0..64 {
+            for sample in 0..=255 {
+                assert!(!rct.push(sample) && !apt.push(sample));
+            }
+        }
+    }
+}
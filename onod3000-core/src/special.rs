@@ -0,0 +1,128 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use core::f64::consts::SQRT_2;
+
+use libm::{erfc, exp, fabs, lgamma, log};
+
+/// Relative accuracy at which the series and continued fractions stop.
+const EPSILON: f64 = 1e-15;
+
+/// Most terms of a series or continued fraction.
+const MAX_ITERATIONS: usize = 10_000;
+
+/// Two-tailed p-value of a standard normal z-score.
+pub fn normal_p_value(z: f64) -> f64 {
+    erfc(fabs(z) / SQRT_2)
+}
+
+/// Probability of a chi-square statistic of at least `statistic` with `degrees_of_freedom`.
+pub fn chi_square_p_value(statistic: f64, degrees_of_freedom: f64) -> f64 {
+    upper_gamma(degrees_of_freedom / 2.0, statistic / 2.0)
+}
+
+/// Regularized upper incomplete gamma function Q(a, x), by its series below x = a + 1 and by its continued
+/// fraction above (Numerical Recipes, 6.2).
+pub fn upper_gamma(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = exp(-x + a * log(x) - lgamma(a));
+    if x < a + 1.0 {
+        let (mut term, mut sum) = (1.0 / a, 1.0 / a);
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if fabs(term) < fabs(sum) * EPSILON {
+                break;
+            }
+        }
+        1.0 - sum * prefactor
+    } else {
+        // Modified Lentz's method
+        let tiny = f64::MIN_POSITIVE / EPSILON;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..MAX_ITERATIONS {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if fabs(d) < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if fabs(c) < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if fabs(delta - 1.0) < EPSILON {
+                break;
+            }
+        }
+        h * prefactor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+    use statrs::function::gamma::gamma_ur;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!(fabs(value - expected) <= 1e-12 + 1e-9 * expected, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn normal_p_value_matches_statrs() {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+        for z in [-6.0, -2.5, -1.0, -0.1, 0.0, 0.3, 1.96, 3.0, 8.0] {
+            assert_close(normal_p_value(z), 2.0 * normal.sf(fabs(z)));
+        }
+    }
+
+    #[test]
+    fn chi_square_p_value_matches_statrs() {
+        for degrees_of_freedom in [1.0, 8.0, 9.0, 255.0] {
+            let chi_squared = ChiSquared::new(degrees_of_freedom).unwrap();
+            for statistic in [0.0, 0.5, 1.0, 7.5, 20.0, 255.0, 400.0] {
+                assert_close(chi_square_p_value(statistic, degrees_of_freedom), chi_squared.sf(statistic));
+            }
+        }
+    }
+
+    #[test]
+    fn upper_gamma_matches_statrs() {
+        for a in [0.5, 1.0, 1.5, 4.5, 127.5] {
+            for x in [0.01, 0.5, 2.0, 5.5, 50.0, 200.0] {
+                assert_close(upper_gamma(a, x), gamma_ur(a, x));
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+use libm::sqrt;
+
+use crate::special::chi_square_p_value;
+
+// Lookup table for the number of set bits in each byte (Hamming weight)
+const SET_BITS_PER_BYTE: [usize; 256] = [
+    0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4, 1, 2, 2, 3, 2, 3, 3, 4, 2, 3, 3, 4, 3, 4, 4, 5, 1, 2, 2, 3,
+    2, 3, 3, 4, 2, 3, 3, 4, 3, 4, 4, 5, 2, 3, 3, 4, 3, 4, 4, 5, 3, 4, 4, 5, 4, 5, 5, 6, 1, 2, 2, 3, 2, 3, 3, 4,
+    2, 3, 3, 4, 3, 4, 4, 5, 2, 3, 3, 4, 3, 4, 4, 5, 3, 4, 4, 5, 4, 5, 5, 6, 2, 3, 3, 4, 3, 4, 4, 5, 3, 4, 4, 5,
+    4, 5, 5, 6, 3, 4, 4, 5, 4, 5, 5, 6, 4, 5, 5, 6, 5, 6, 6, 7, 1, 2, 2, 3, 2, 3, 3, 4, 2, 3, 3, 4, 3, 4, 4, 5,
+    2, 3, 3, 4, 3, 4, 4, 5, 3, 4, 4, 5, 4, 5, 5, 6, 2, 3, 3, 4, 3, 4, 4, 5, 3, 4, 4, 5, 4, 5, 5, 6, 3, 4, 4, 5,
+    4, 5, 5, 6, 4, 5, 5, 6, 5, 6, 6, 7, 2, 3, 3, 4, 3, 4, 4, 5, 3, 4, 4, 5, 4, 5, 5, 6, 3, 4, 4, 5, 4, 5, 5, 6,
+    4, 5, 5, 6, 5, 6, 6, 7, 3, 4, 4, 5, 4, 5, 5, 6, 4, 5, 5, 6, 5, 6, 6, 7, 4, 5, 5, 6, 5, 6, 6, 7, 5, 6, 6, 7,
+    6, 7, 7, 8,
+];
+
+// Expected number of occurrences for each Hamming weight
+const EXPECTED_NUMBER: [f64; 9] = [1.0, 8.0, 28.0, 56.0, 70.0, 56.0, 28.0, 8.0, 1.0];
+
+/// Streaming state of the chi-squared bit test.
+#[derive(Clone, Debug, Default)]
+pub struct ChiBit {
+    // Frequency count of Hamming weights
+    frequency: [u64; 9],
+}
+
+impl ChiBit {
+    pub fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            let hamming_weight = SET_BITS_PER_BYTE[byte as usize];
+            self.frequency[hamming_weight] += 1;
+        }
+    }
+
+    pub fn finish(&self) -> (f64, f64, f64) {
+
+        let total_samples: u64 = self.frequency.iter().sum();
+        if total_samples == 0 {
+            return (-1.0, 0.0, 1.0); // empty data
+        }
+
+        // Chi-squared statistic of the observed against the expected counts
+        let total_samples = total_samples as f64;
+        let chi_squared_stat = self.frequency
+            .iter()
+            .zip(EXPECTED_NUMBER.iter())
+            .map(|(&observed, &e)| {
+                let expected = e / 256.0 * total_samples;
+                (observed as f64 - expected) * (observed as f64 - expected) / expected
+            })
+            .sum();
+
+        // Degrees of freedom: 9 categories - 1
+        let degrees_of_freedom = (EXPECTED_NUMBER.len() - 1) as f64;
+        let p_value = chi_square_p_value(chi_squared_stat, degrees_of_freedom);
+
+        // Z-score calculation (standardization of the chi-squared statistic)
+        let mean = degrees_of_freedom; // Mean of the chi-squared distribution
+        let std_dev = sqrt(2.0 * degrees_of_freedom); // Standard deviation of the chi-squared distribution
+        let z_score = (chi_squared_stat - mean) / std_dev;
+
+        (chi_squared_stat, z_score, p_value)
+    }
+}
+
+/// ChiBit randomness test
+/// Compares the Hamming weights of the bytes with their binomial distribution and returns a p-value.
+pub fn chi_bit(samples: &[u8]) -> (f64, f64, f64) {
+    let mut chi_bit = ChiBit::default();
+    chi_bit.update(samples);
+    chi_bit.finish()
+}
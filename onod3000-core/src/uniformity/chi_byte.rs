@@ -0,0 +1,86 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use libm::sqrt;
+
+use crate::special::chi_square_p_value;
+
+/// Streaming state of the chi-squared byte test.
+#[derive(Clone, Debug)]
+pub struct ChiByte {
+    // Occurrences of each byte value (0-255)
+    counts: [u64; 256],
+}
+
+impl Default for ChiByte {
+    fn default() -> Self {
+        ChiByte { counts: [0; 256] }
+    }
+}
+
+impl ChiByte {
+    pub fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            self.counts[byte as usize] += 1;
+        }
+    }
+
+    pub fn finish(&self) -> (f64, f64, f64) {
+
+        let len: u64 = self.counts.iter().sum();
+        if len == 0 {
+            return (-1.0, 0.0, 1.0); // Default to perfect randomness for empty data
+        }
+
+        // Calculate expected count assuming uniform distribution
+        let expected_count = len as f64 / 256.0;
+
+        // Calculate chi-squared statistic
+        let mut chi_squared_stat = 0.0;
+        for &count in self.counts.iter() {
+            let diff = count as f64 - expected_count;
+            chi_squared_stat += (diff * diff) / expected_count;
+        }
+
+        // Use chi-squared distribution to calculate p-value
+        let degrees_of_freedom = 256.0 - 1.0; // 256 possible byte values - 1
+        let p_value = chi_square_p_value(chi_squared_stat, degrees_of_freedom);
+
+        // Z-score calculation (standardization of the chi-squared statistic)
+        let mean = degrees_of_freedom; // Mean of the chi-squared distribution
+        let std_dev = sqrt(2.0 * degrees_of_freedom); // Standard deviation of the chi-squared distribution
+        let z_score = (chi_squared_stat - mean) / std_dev;
+
+        (chi_squared_stat, z_score, p_value)
+    }
+}
+
+/// ChiByte randomness test
+/// Evaluates the uniformity of byte values across the data and returns a p-value.
+pub fn chi_byte(samples: &[u8]) -> (f64, f64, f64) {
+    let mut chi_byte = ChiByte::default();
+    chi_byte.update(samples);
+    chi_byte.finish()
+}
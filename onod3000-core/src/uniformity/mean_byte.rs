@@ -0,0 +1,72 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use libm::sqrt;
+
+use crate::special::normal_p_value;
+
+/// Streaming state of the mean byte test.
+#[derive(Clone, Debug, Default)]
+pub struct MeanByte {
+    sum: u64,
+    len: u64,
+}
+
+impl MeanByte {
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.sum += chunk.iter().map(|&x| x as u64).sum::<u64>();
+        self.len += chunk.len() as u64;
+    }
+
+    pub fn finish(&self) -> (f64, f64, f64) {
+
+        let len = self.len as f64;
+        if len == 0.0 {
+            return (-1.0, 0.0, 1.0);
+        }
+
+        // Calculate observed mean
+        let observed_mean: f64 = self.sum as f64 / len;
+
+        // Expected mean for uniform distribution
+        let expected_mean = 127.5;
+
+        // Calculate standard deviation of the mean
+        let std_dev_mean = sqrt((256.0 * 256.0 - 1.0) / (12.0 * len));
+
+        // Calculate the z-score
+        let z_score = (observed_mean - expected_mean) / std_dev_mean;
+
+        (observed_mean, z_score, normal_p_value(z_score)) // Two-tailed test
+    }
+}
+
+/// Mean randomness test
+/// Calculates the p-value for the mean of the byte slice compared to expected mean.
+pub fn mean_byte(samples: &[u8]) -> (f64, f64, f64) {
+    let mut mean_byte = MeanByte::default();
+    mean_byte.update(samples);
+    mean_byte.finish()
+}
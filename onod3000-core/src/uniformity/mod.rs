@@ -0,0 +1,153 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+pub mod chi_bit;
+pub mod chi_byte;
+pub mod mean_byte;
+pub mod monobit;
+pub mod prediction;
+pub mod runups;
+
+pub use chi_bit::{chi_bit, ChiBit};
+pub use chi_byte::{chi_byte, ChiByte};
+pub use mean_byte::{mean_byte, MeanByte};
+pub use monobit::{monobit, Monobit};
+pub use prediction::{prediction, Prediction};
+pub use runups::{run_ups, RunUps};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libm::{fabs, sqrt};
+    use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+
+    // The std implementations these tests replaced, written with statrs
+    fn normal_p(z: f64) -> f64 {
+        2.0 * (1.0 - Normal::new(0.0, 1.0).unwrap().cdf(fabs(z)))
+    }
+
+    fn chi_square(observed: &[f64], expected: &[f64], degrees_of_freedom: f64) -> (f64, f64, f64) {
+        let statistic: f64 = observed.iter().zip(expected).map(|(o, e)| (o - e) * (o - e) / e).sum();
+        let p_value = 1.0 - ChiSquared::new(degrees_of_freedom).unwrap().cdf(statistic);
+        (statistic, (statistic - degrees_of_freedom) / sqrt(2.0 * degrees_of_freedom), p_value)
+    }
+
+    fn reference_monobit(samples: &[u8]) -> (f64, f64, f64) {
+        let bits = 8.0 * samples.len() as f64;
+        let proportion = samples.iter().map(|byte| byte.count_ones() as f64).sum::<f64>() / bits;
+        let z = (proportion - 0.5) / sqrt(0.25 / bits);
+        (proportion, z, normal_p(z))
+    }
+
+    fn reference_chi_bit(samples: &[u8]) -> (f64, f64, f64) {
+        let mut observed = [0.0; 9];
+        samples.iter().for_each(|byte| observed[byte.count_ones() as usize] += 1.0);
+        let binomial = [1.0, 8.0, 28.0, 56.0, 70.0, 56.0, 28.0, 8.0, 1.0];
+        let expected = binomial.map(|count: f64| count / 256.0 * samples.len() as f64);
+        chi_square(&observed, &expected, 8.0)
+    }
+
+    fn reference_chi_byte(samples: &[u8]) -> (f64, f64, f64) {
+        let mut observed = [0.0; 256];
+        samples.iter().for_each(|&byte| observed[byte as usize] += 1.0);
+        chi_square(&observed, &[samples.len() as f64 / 256.0; 256], 255.0)
+    }
+
+    fn reference_mean_byte(samples: &[u8]) -> (f64, f64, f64) {
+        let len = samples.len() as f64;
+        let mean = samples.iter().map(|&byte| byte as f64).sum::<f64>() / len;
+        let z = (mean - 127.5) / sqrt((256.0 * 256.0 - 1.0) / (12.0 * len));
+        (mean, z, normal_p(z))
+    }
+
+    fn reference_run_ups(samples: &[u8]) -> (f64, f64, f64) {
+        let runs = samples.chunks_exact(4).filter(|run| run[0] < run[1] && run[1] < run[2] && run[2] < run[3]);
+        let (ups, total) = (runs.count() as f64, (samples.len() / 4) as f64);
+        let expected = 2_731_135.0 / 67_108_864.0 * total;
+        chi_square(&[ups, total - ups], &[expected, total - expected], 1.0)
+    }
+
+    fn reference_prediction(samples: &[u8]) -> (f64, f64, f64) {
+        let (mut prediction, mut correct) = (0, 0.0);
+        for (i, &sample) in samples.iter().enumerate() {
+            if i >= 2 && prediction == sample {
+                correct += 1.0;
+            }
+            prediction ^= sample;
+        }
+        let len = samples.len() as f64;
+        let (_, z, p) = chi_square(&[correct, len - correct], &[len / 256.0, 255.0 * len / 256.0], 1.0);
+        (len - 2.0, z, p)
+    }
+
+    /// Pseudorandom bytes from xorshift32, optionally with the top bit forced to bias them.
+    fn samples<const N: usize>(seed: u32, bias: u8) -> [u8; N] {
+        let mut state = seed;
+        core::array::from_fn(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            (state >> 24) as u8 | bias
+        })
+    }
+
+    fn assert_matches(name: &str, core: fn(&[u8]) -> (f64, f64, f64), std: fn(&[u8]) -> (f64, f64, f64)) {
+        let random: [u8; 100_000] = samples(1, 0);
+        let biased: [u8; 4_000] = samples(2, 0x80);
+        let short: [u8; 40] = samples(3, 0);
+        for input in [&random[..], &biased[..], &short[..]] {
+            let (core, std) = (core(input), std(input));
+            for (value, expected) in [core.0, core.1, core.2].into_iter().zip([std.0, std.1, std.2]) {
+                assert!(fabs(value - expected) <= 1e-9 * fabs(expected).max(1.0), "{}: {:?} != {:?}", name, core, std);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_std_implementations() {
+        assert_matches("monobit", monobit, reference_monobit);
+        assert_matches("chi_bit", chi_bit, reference_chi_bit);
+        assert_matches("chi_byte", chi_byte, reference_chi_byte);
+        assert_matches("mean_byte", mean_byte, reference_mean_byte);
+        assert_matches("run_ups", run_ups, reference_run_ups);
+        assert_matches("prediction", prediction, reference_prediction);
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let input: [u8; 10_007] = samples(4, 0);
+        let mut monobit = Monobit::default();
+        let mut run_ups = RunUps::default();
+        let mut prediction = Prediction::default();
+        for chunk in input.chunks(333) {
+            monobit.update(chunk);
+            run_ups.update(chunk);
+            prediction.update(chunk);
+        }
+        assert_eq!(monobit.finish(), super::monobit(&input));
+        assert_eq!(run_ups.finish(), super::run_ups(&input));
+        assert_eq!(prediction.finish(), super::prediction(&input));
+    }
+}
@@ -0,0 +1,72 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use libm::sqrt;
+
+use crate::special::normal_p_value;
+
+/// Streaming state of the monobit test.
+#[derive(Clone, Debug, Default)]
+pub struct Monobit {
+    total_ones: u64,
+    total_bits: u64,
+}
+
+impl Monobit {
+    pub fn update(&mut self, chunk: &[u8]) {
+        // Count the total number of 1s in the dataset
+        for &byte in chunk {
+            self.total_ones += byte.count_ones() as u64;
+            self.total_bits += 8;
+        }
+    }
+
+    pub fn finish(&self) -> (f64, f64, f64) {
+
+        if self.total_bits == 0 {
+            return (-1.0, 0.0, 1.0); // Default to perfect randomness for empty data
+        }
+
+        // Calculate the observed proportion of 1s
+        let observed_proportion = self.total_ones as f64 / self.total_bits as f64;
+
+        // Expected proportion for random data
+        let expected_proportion = 0.5;
+        let std_dev = sqrt(0.5 * 0.5 / self.total_bits as f64); // Standard deviation for a binomial distribution
+
+        // Calculate the z-score
+        let z_score = (observed_proportion - expected_proportion) / std_dev;
+
+        (observed_proportion, z_score, normal_p_value(z_score))
+    }
+}
+
+/// Monobit randomness test
+/// Evaluates the balance of 0s and 1s in the binary representation of the data and returns a p-value.
+pub fn monobit(samples: &[u8]) -> (f64, f64, f64) {
+    let mut monobit = Monobit::default();
+    monobit.update(samples);
+    monobit.finish()
+}
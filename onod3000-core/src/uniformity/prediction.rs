@@ -0,0 +1,107 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use core::f64::consts::SQRT_2;
+
+use crate::special::chi_square_p_value;
+
+/* 
+ * Blatantly copied from David Sexton's battery.
+ *
+ * An algorithm is used to predict the value of each byte of the sequence from 
+ * the beginning of the sequence to the end. In a random sequence the 
+ * probability of success of any such algorithm is 1/256. The number of successes 
+ * is counted. A chi-squared statistic is calculated. The degrees-of-freedom is 1. 
+ * The algorithm is as follows: the next byte is predicted to be equal to all the 
+ * previous bytes bitwise XORed together.
+ */
+
+/// Streaming state of the prediction test.
+#[derive(Clone, Debug, Default)]
+pub struct Prediction {
+    // All preceding bytes XORed together
+    prediction: u8,
+    len: u64,
+    correct_predictions: u64,
+}
+
+impl Prediction {
+    pub fn update(&mut self, chunk: &[u8]) {
+        for &sample in chunk {
+            // Predictions start with the third byte
+            if self.len >= 2 && self.prediction == sample {
+                self.correct_predictions += 1;
+            }
+            self.prediction ^= sample;
+            self.len += 1;
+        }
+    }
+
+    pub fn finish(&self) -> (f64, f64, f64) {
+        if self.len < 3 {
+            return (-1.0, 0.0, 1.0); // Not enough data for meaningful calculation
+        }
+
+        let len = self.len as f64;
+        let correct_predictions = self.correct_predictions as f64;
+        let total_predictions = self.len - 2;
+
+        // Calculate expected and observed frequencies
+        let expected = [
+            (1.0 / 256.0) * len, // Probability of correct prediction
+            (255.0 / 256.0) * len, // Probability of incorrect prediction
+        ];
+        let observed = [
+            correct_predictions, // Actual correct predictions
+            len - correct_predictions, // Actual incorrect predictions
+        ];
+
+        // Calculate chi-squared statistic
+        let chi_squared_stat: f64 = observed
+            .iter()
+            .zip(expected.iter())
+            .map(|(o, e)| (o - e) * (o - e) / e)
+            .sum();
+
+        // Use Chi-Squared distribution to calculate p-value
+        let p_value = chi_square_p_value(chi_squared_stat, 1.0); // Degrees of freedom = 1
+
+        // Calculate z-score (optional, for diagnostics)
+        let mean = 1.0; // Mean of chi-squared distribution
+        let std_dev = SQRT_2; // Standard deviation of chi-squared distribution
+        let z_score = (chi_squared_stat - mean) / std_dev;
+
+        (total_predictions as f64, z_score, p_value)
+    }
+}
+
+/// Prediction randomness test
+/// Evaluates the predictability of the next byte based on XORing the previous bytes
+/// and returns the total predictions, z-score, and p-value.
+pub fn prediction(samples: &[u8]) -> (f64, f64, f64) {
+    let mut prediction = Prediction::default();
+    prediction.update(samples);
+    prediction.finish()
+}
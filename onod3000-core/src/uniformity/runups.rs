@@ -0,0 +1,92 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use core::f64::consts::SQRT_2;
+
+use crate::special::chi_square_p_value;
+
+/// Streaming state of the run-ups test.
+#[derive(Clone, Debug, Default)]
+pub struct RunUps {
+    // Bytes of the current, not yet complete block of four
+    block: [u8; 4],
+    filled: usize,
+    test_statistic: u64,
+    total_chunks: u64,
+}
+
+impl RunUps {
+    pub fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            self.block[self.filled] = byte;
+            self.filled += 1;
+            if self.filled == 4 {
+                let run = self.block;
+                if run[0] < run[1] && run[1] < run[2] && run[2] < run[3] {
+                    self.test_statistic += 1;
+                }
+                self.total_chunks += 1;
+                self.filled = 0;
+            }
+        }
+    }
+
+    pub fn finish(&self) -> (f64, f64, f64) {
+
+        if self.total_chunks == 0 {
+            return (-1.0, 0.0, 1.0); // Not enough data for meaningful calculation
+        }
+
+        let test_statistic = self.test_statistic as f64;
+        let total_chunks = self.total_chunks as f64;
+        let no_expected = 2_731_135.0 / 67_108_864.0 * total_chunks;
+
+        let observed = [test_statistic, total_chunks - test_statistic];
+        let expected = [no_expected, total_chunks - no_expected];
+
+        // Use chi-squared test to calculate p-value
+        let chi_squared_stat: f64 = observed.iter()
+            .zip(expected.iter())
+            .map(|(o, e)| (o - e) * (o - e) / e)
+            .sum();
+
+        let p_value = chi_square_p_value(chi_squared_stat, 1.0);
+
+        // Z-score calculation (standardization of the chi-squared statistic)
+        let mean = 1.0; // Mean of the chi-squared distribution
+        let std_dev = SQRT_2; // Standard deviation of the chi-squared distribution
+        let z_score = (chi_squared_stat - mean) / std_dev;
+
+        (chi_squared_stat, z_score, p_value)
+    }
+}
+
+/// RunUps randomness test
+/// Evaluates the number of four-byte run-ups in the data and returns a p-value.
+pub fn run_ups(input: &[u8]) -> (f64, f64, f64) {
+    let mut run_ups = RunUps::default();
+    run_ups.update(input);
+    run_ups.finish()
+}
@@ -30,94 +30,371 @@ use std::fs::File;
 use std::io::{self, Read};
 use std::path::Path;
 
-use onod3000::Onod;
+use onod3000::{entropy, Onod};
+use onod3000::entropy::assessment::Assessment;
+use onod3000::entropy::restart::{RestartMatrix, RestartReport, RESTART_SAMPLES};
+use onod3000::profile::{Correction, Profile, Suite, Verdict, PRESETS};
+use onod3000::stream::TestResult;
+use onod3000::transform::parse_transforms;
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: {} [-f <file_path>] [--profile <profile.toml>] [--suite <name>] \
+         [--transform <transform>[,<transform>...]] \
+         [--condition <function>[:<n_in>[:<n_out>]] [--condition-key <hex>] [--h-in <bits per byte>]]",
+        std::env::args().next().unwrap()
+    );
+    eprintln!(
+        "       {} [-f <file_path>] --assess [--bits <1|2|4|8>] [--seed <n>] [--json] [--condition <function>[:<n_in>[:<n_out>]]]",
+        std::env::args().next().unwrap()
+    );
+    eprintln!(
+        "       {} --restart <file or directory> --h-initial <bits per sample> [--bits <1|2|4|8>] [--iid]",
+        std::env::args().next().unwrap()
+    );
+    eprintln!("Built-in suites: {}", PRESETS.join(", "));
+    eprintln!("Transforms: von_neumann, peres[:depth], parity:<n> (alias xor_fold:<n>), decimate:<k>");
+    eprintln!("Conditioning functions: sha256, hmac_sha256, cbc_mac");
+    std::process::exit(1);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    std::process::exit(1);
+}
+
+fn print_results(bytes: u64, source: &str, suite: &Suite, results: &[TestResult]) {
+    println!("\nTesting {} bytes from {}.", bytes, source);
+    println!("--------------------------------------------------------");
+    println!("Randomness Test           Value   Z-Score   P-Value Pass");
+    println!("--------------------------------------------------------");
+
+    let verdicts = suite.verdicts(results);
+    let mut passed_tests = 0;
+
+    for ((test, (observation, z_score, p_value)), verdict) in suite.tests.iter().zip(results).zip(&verdicts) {
+        let result = match verdict {
+            Verdict::Pass => {
+                passed_tests += 1;
+                "✅"
+            }
+            Verdict::Skip => "SKIP",
+            Verdict::Fail => "❌",
+        };
+
+        println!(
+            "{:<15} {:>15.3}  {:>8.4}  {:>7.4}  {:>2}",
+            Onod::test_name(test), observation, z_score, p_value, result
+        );
+    }
+
+    println!("--------------------------------------------------------");
+    let skipped_tests = verdicts.iter().filter(|&&verdict| verdict == Verdict::Skip).count();
+    match skipped_tests {
+        0 => println!("{}/{} tests passed.", passed_tests, results.len()),
+        _ => println!("{}/{} tests passed, {} skipped.", passed_tests, results.len() - skipped_tests, skipped_tests),
+    }
+    if suite.correction != Correction::None || suite.alpha != 0.01 {
+        println!("Alpha {} with {:?} correction.", suite.alpha, suite.correction);
+    }
+    println!("--------------------------------------------------------");
+}
+
+fn print_restart(source: &str, matrix: &RestartMatrix, report: &RestartReport) {
+    let verdict = |passed: bool| if passed { "✅" } else { "❌" };
+    println!(
+        "\nRestart tests on {} restarts of {} samples of {} bit(s) from {}.",
+        matrix.rows, matrix.columns, matrix.bits_per_symbol, source
+    );
+    if !matrix.is_full_size() {
+        println!("SP 800-90B requires 1000 restarts of 1000 samples for validation.");
+    }
+    println!("--------------------------------------------------------");
+    println!("Sanity Check              Most Common   Cutoff      Pass");
+    println!("--------------------------------------------------------");
+    let sanity = &report.sanity;
+    println!("{:<25} {:>11}   {:>6}      {:>2}", "Rows", sanity.row_max, sanity.row_cutoff, verdict(sanity.row_max < sanity.row_cutoff));
+    println!(
+        "{:<25} {:>11}   {:>6}      {:>2}",
+        "Columns", sanity.column_max, sanity.column_cutoff, verdict(sanity.column_max < sanity.column_cutoff)
+    );
+    println!("--------------------------------------------------------");
+    println!("Min-Entropy                     H_min   H_I / 2      Pass");
+    println!("--------------------------------------------------------");
+    for (dataset, h) in [("Rows (H_r)", report.h_r()), ("Columns (H_c)", report.h_c())] {
+        println!("{:<25} {:>11.6}   {:>7.4}    {:>2}", dataset, h, report.h_initial / 2.0, verdict(h >= report.h_initial / 2.0));
+    }
+    println!("--------------------------------------------------------");
+    if report.passed() {
+        println!(
+            "Restart tests passed. Min-entropy: {:.6} bits per sample (H_I = {:.6}).",
+            report.min_entropy(), report.h_initial
+        );
+    } else {
+        println!("Restart tests failed.");
+    }
+    println!("--------------------------------------------------------");
+}
+
+fn print_assessment(source: &str, assessment: &Assessment) {
+    let verdict = |passed: bool| if passed { "✅" } else { "❌" };
+    println!(
+        "\nEntropy assessment of {} samples of {} bit(s) from {}.",
+        assessment.samples, assessment.bits_per_symbol, source
+    );
+    println!("----------------------------------------------------------------");
+    println!("IID Test                        Statistic   Rank / P-Value  Pass");
+    println!("----------------------------------------------------------------");
+    let iid = &assessment.iid;
+    for test in &iid.permutation.tests {
+        let rank = format!("{}/{}/{}", test.greater, test.equal, test.less);
+        println!("{:<24} {:>16.4}   {:>14}   {:>2}", test.name, test.statistic, rank, verdict(test.passed()));
+    }
+    for test in &iid.chi_square {
+        println!("{:<24} {:>16.4}   {:>14.4}   {:>2}", test.name, test.statistic, test.p_value, verdict(test.passed()));
+    }
+    println!(
+        "{:<24} {:>16}   {:>14.4}   {:>2}",
+        "longest_repeat", iid.lrs.length, iid.lrs.probability, verdict(iid.lrs.passed())
+    );
+    println!("----------------------------------------------------------------");
+    println!(
+        "Ranks count shuffles with greater/equal/smaller statistics, {} shuffles drawn from seed {}.",
+        iid.permutation.shuffles, assessment.seed
+    );
+    match assessment.is_iid() {
+        true => println!("The samples are IID, assessed by the most common value estimate."),
+        false => println!("The samples are not IID, assessed by the non-IID estimators."),
+    }
+    println!("----------------------------------------------------------------");
+    println!("Min-Entropy Estimate      Data                  Bits per Sample");
+    println!("----------------------------------------------------------------");
+    let estimates = &assessment.estimates;
+    for estimate in &estimates.original {
+        println!("{:<25} {:<10} {:>26.6}", estimate.name, "original", estimate.entropy);
+    }
+    for estimate in &estimates.bitstring {
+        let data = if estimates.bits_per_symbol == 1 { "original" } else { "bitstring" };
+        println!(
+            "{:<25} {:<10} {:>26.6}",
+            estimate.name, data, estimate.entropy * estimates.bits_per_symbol as f64
+        );
+    }
+    println!("----------------------------------------------------------------");
+    if let Some(h) = estimates.h_original() {
+        println!("H_original  {:.6}", h);
+    }
+    println!("H_bitstring {:.6} per bit", estimates.h_bitstring());
+    println!("H_min       {:.6} bits per sample", assessment.h_min());
+    if let Some(claim) = &assessment.conditioning {
+        println!("----------------------------------------------------------------");
+        println!(
+            "Conditioning with {}: n_in = {}, n_out = {}, nw = {}.",
+            claim.function, claim.input_bits, claim.output_bits, claim.width
+        );
+        println!("h_in  = {:.6} bits per input block ({} samples)", claim.h_in, claim.input_bits / assessment.bits_per_symbol);
+        println!(
+            "h_out = {:.6} bits per output block ({:.6} per output bit)",
+            claim.h_out, claim.h_out / claim.output_bits as f64
+        );
+    }
+    println!("----------------------------------------------------------------");
+}
 
 fn main() -> io::Result<()> {
     let mut args = std::env::args();
     args.next(); // Skip program name
 
-    let mut input_data = Vec::new();
-    let mut source = String::from("stdin");
+    let mut file_path = None;
+    let mut profile_path = None;
+    let mut suite_name = None;
+    let mut transforms = Vec::new();
+    let mut condition = None;
+    let mut condition_key = None;
+    let mut h_in = None;
+    let mut selection: [Option<u64>; 5] = [None; 5]; // offset, length, skip suffix, stride, word
+    let mut restart = None;
+    let mut h_initial = None;
+    let mut bits_per_symbol = 8;
+    let mut iid = false;
+    let mut assess = false;
+    let mut seed = 1;
+    let mut json = false;
 
-    if let Some(arg) = args.next() {
-        if arg == "-f" {
-            if let Some(file_path) = args.next() {
-                source = file_path.clone();
-                let path = Path::new(&file_path);
-                let mut file = File::open(path)?;
-                file.read_to_end(&mut input_data)?;
-            } else {
-                eprintln!("Error: No file path provided after -f.");
-                std::process::exit(1);
+    while let Some(arg) = args.next() {
+        let mut value = |what: &str| args.next().unwrap_or_else(|| fail(&format!("No {} provided after {}.", what, arg)));
+        match arg.as_str() {
+            "-f" => file_path = Some(value("file path")),
+            "--profile" => profile_path = Some(value("profile")),
+            "--suite" => suite_name = Some(value("suite name")),
+            "--transform" => match parse_transforms(&value("transform")) {
+                Ok(parsed) => transforms.extend(parsed),
+                Err(e) => fail(&e),
+            },
+            "--offset" | "--skip-prefix" | "--length" | "--skip-suffix" | "--stride" | "--word" => {
+                let index = ["--offset", "--length", "--skip-suffix", "--stride", "--word"]
+                    .iter()
+                    .position(|&name| name == arg || (arg == "--skip-prefix" && name == "--offset"))
+                    .unwrap();
+                match value("number").parse::<u64>() {
+                    Ok(n) => selection[index] = Some(n),
+                    Err(_) => fail(&format!("{} takes a whole number.", arg)),
+                }
             }
-        } else {
-            eprintln!("Usage: {} [-f <file_path>]", std::env::args().next().unwrap());
-            std::process::exit(1);
+            "--condition" => condition = Some(value("conditioning function")),
+            "--condition-key" => condition_key = Some(value("key")),
+            "--h-in" => match value("min-entropy").parse::<f64>() {
+                Ok(h) => h_in = Some(h),
+                Err(_) => fail("--h-in takes the min-entropy per input byte, between 0 and 8."),
+            },
+            "--restart" => restart = Some(value("restart data")),
+            "--h-initial" => match value("min-entropy").parse::<f64>() {
+                Ok(h) => h_initial = Some(h),
+                Err(_) => fail("--h-initial takes the min-entropy per sample of the sequential data."),
+            },
+            "--bits" => match value("symbol width").parse::<usize>() {
+                Ok(bits @ (1 | 2 | 4 | 8)) => bits_per_symbol = bits,
+                _ => fail("--bits takes the bits per sample: 1, 2, 4 or 8."),
+            },
+            "--iid" => iid = true,
+            "--assess" => assess = true,
+            "--seed" => match value("seed").parse::<u32>() {
+                Ok(n) => seed = n,
+                Err(_) => fail(&format!("--seed takes a whole number between 0 and {}.", u32::MAX)),
+            },
+            "--json" => json = true,
+            _ => usage(),
         }
-    } else {
-        io::stdin().read_to_end(&mut input_data)?;
     }
 
-    if input_data.is_empty() {
-        eprintln!("Error: No input data provided.");
-        std::process::exit(1);
+    if let Some(path) = restart {
+        let h_initial = match h_initial {
+            Some(h) if h > 0.0 && h <= bits_per_symbol as f64 => h,
+            Some(_) => fail(&format!("--h-initial must be above 0 and at most {} bits per sample.", bits_per_symbol)),
+            None => fail("Restart tests need the min-entropy of the sequential data, pass it with --h-initial."),
+        };
+        let matrix = RestartMatrix::load(&path, bits_per_symbol, RESTART_SAMPLES).unwrap_or_else(|e| fail(&e));
+        let report = Onod::restart_tests(&matrix, h_initial, iid);
+        print_restart(&path, &matrix, &report);
+        return Ok(());
     }
 
-    println!("\nTesting {} bytes from {}.", input_data.len(), source);
-    println!("--------------------------------------------------------");
-    println!("Randomness Test           Value   Z-Score   P-Value Pass");
-    println!("--------------------------------------------------------");
+    // Command line options extend or override the selected suite
+    let mut suite = match profile_path {
+        Some(path) => {
+            let profile = Profile::load(&path).unwrap_or_else(|e| fail(&e));
+            profile.suite(suite_name.as_deref()).unwrap_or_else(|e| fail(&e)).clone()
+        }
+        None => match suite_name {
+            Some(name) => Suite::preset(&name).unwrap_or_else(|| {
+                fail(&format!("No built-in suite named '{}', choose one of: {}", name, PRESETS.join(", ")))
+            }),
+            None => Suite::default(),
+        },
+    };
+    suite.transforms.extend(transforms);
+    if condition.is_some() {
+        suite.condition = condition;
+    }
+    if condition_key.is_some() {
+        suite.condition_key = condition_key;
+    }
+    if h_in.is_some() {
+        suite.h_in = h_in;
+    }
+    let [offset, length, skip_suffix, stride, word] = selection;
+    let select = &mut suite.selection;
+    select.offset = offset.unwrap_or(select.offset);
+    select.length = length.or(select.length);
+    select.skip_suffix = skip_suffix.unwrap_or(select.skip_suffix);
+    select.stride = stride.unwrap_or(select.stride);
+    select.word = word.map_or(select.word, |w| w as usize);
+    suite.validate().unwrap_or_else(|e| fail(&e));
 
-    let mut passed_tests = 0;
-    let alpha = 0.01;
-
-    // Create vectors to store results
-    let (mut observations, mut z_scores, mut p_values) = (Vec::new(), Vec::new(), Vec::new());
-
-    // Run each test with the appropriate handling
-    let tests = [
-        ("Shannon",       Onod::shannon(&input_data)),
-        ("Monobit",       Onod::monobit(&input_data)),
-        ("ChiBit",        Onod::chi_bit(&input_data)),
-        ("ChiByte",       Onod::chi_byte(&input_data)),
-        ("MeanByte",      Onod::mean_byte(&input_data)),
-        ("Compression",   Onod::compression(&input_data)),
-        ("Kolm.-Smirnov", Onod::ks(&input_data)),
-        ("Pi",            Onod::pi(&input_data)),
-        ("Shells",        Onod::shells(&input_data)),
-        ("Gaps",          Onod::gaps(&input_data)),
-        ("Avalanche",     Onod::avalanche(&input_data)),
-        ("Runs",          Onod::runs(&input_data)),
-        ("RunUps",        Onod::run_ups(&input_data)),
-        ("Prediction",    Onod::prediction(&input_data)),
-        ("UnCorrelation", Onod::uncorrelation(&input_data)),
-    ];
-
-    for (test_name, (observation, z_score, p_value)) in &tests {
-        let result = if *p_value >= alpha && *observation != -1.0 {
-            passed_tests += 1;
-            "✅"
-        } else {
-            if *observation == -1.0 {
-                "SKIP"
-            } else {
-                "❌"
-            } 
-        };
+    // Input is consumed in chunks so that memory stays bounded regardless of its size
+    let mut source = String::from("stdin");
+    let input: Box<dyn Read> = match file_path {
+        Some(file_path) => {
+            source = file_path.clone();
+            let path = Path::new(&file_path);
+            Box::new(File::open(path)?)
+        }
+        None => Box::new(io::stdin().lock()),
+    };
+
+    if assess {
+        let samples = entropy::symbols(&suite.read_samples(input)?, bits_per_symbol);
+        if samples.is_empty() {
+            fail("No input data provided.");
+        }
+        let conditioner = suite.conditioner().unwrap_or_else(|e| fail(&e));
+        let assessment = Onod::entropy_assessment(&samples, bits_per_symbol, seed, conditioner.as_ref());
+        match json {
+            true => println!("{}", assessment.to_json()),
+            false => print_assessment(&source, &assessment),
+        }
+        return Ok(());
+    }
+
+    let report = suite.run(input)?;
+
+    if report.input_bytes == 0 {
+        fail("No input data provided.");
+    }
+
+    if !suite.selection.is_everything() {
+        let selected = &report.selection;
+        print!("\nSelected bytes {} to {} of {}", selected.start, selected.end, selected.input_bytes);
+        if suite.selection.stride > 1 {
+            print!(", keeping 1 of every {} words of {} byte(s)", suite.selection.stride, suite.selection.word);
+        }
+        println!(": {} bytes.", selected.selected_bytes);
+    }
+
+    if !report.transforms.is_empty() {
+        println!("\nTransforming {} bytes from {}.", report.input_bytes, source);
+        for stat in &report.transforms {
+            println!(
+                "{:<15} {:>12} -> {:>12} bits  ({:.3}:1)",
+                stat.transform.to_string(), stat.bits_in, stat.bits_out, stat.ratio()
+            );
+        }
+    }
 
+    if let Some((raw_bytes, raw_results)) = &report.raw {
+        print_results(*raw_bytes, &format!("{} (raw)", source), &suite, raw_results);
+    }
+
+    if let Some(conditioning) = &report.conditioning {
         println!(
-            "{:<15} {:>15.3}  {:>8.4}  {:>7.4}  {:>2}",
-            test_name, observation, z_score, p_value, result
+            "\nConditioning with {}: {} blocks of {} -> {} bits (width {}).",
+            conditioning.function, conditioning.blocks, conditioning.input_bits,
+            conditioning.output_bits, conditioning.function.width()
         );
+        match (conditioning.output_entropy, suite.h_in) {
+            (Some(h_out), Some(h)) => {
+                println!(
+                    "Output entropy claim: {:.6} bits per {}-bit block ({:.6} per output bit) from h_in = {:.3} bits.",
+                    h_out, conditioning.output_bits, h_out / conditioning.output_bits as f64,
+                    h * conditioning.input_bits as f64 / 8.0
+                );
+            }
+            _ => println!("Pass --h-in <bits per byte> to compute the output entropy claim."),
+        }
+    }
 
-        observations.push(*observation);
-        z_scores.push(*z_score);
-        p_values.push(*p_value);
+    if report.tested_bytes == 0 {
+        fail("No data is left to test after selection, transforms and conditioning.");
     }
 
-    println!("--------------------------------------------------------");
-    println!("{}/{} tests passed.", passed_tests, tests.len());
-    println!("--------------------------------------------------------");
+    let mut tested = source;
+    if !report.transforms.is_empty() {
+        tested = format!("{} after {} transform(s)", tested, report.transforms.len());
+    }
+    if report.conditioning.is_some() {
+        tested = format!("{} (conditioned)", tested);
+    }
+    print_results(report.tested_bytes, &tested, &suite, &report.results);
 
     Ok(())
 }
\ No newline at end of file
@@ -27,8 +27,35 @@
 
 
 use statrs::distribution::{ChiSquared, ContinuousCDF};
+use statrs::function::gamma::gamma_ur;
 
+/// Pearson's chi-square test of observed counts against expected counts or proportions.
+/// Returns the p-value.
 pub fn chi_square_test(observed: &[u64], expected: &[f64]) -> f64 {
+    let chi_squared_stat = chi_square_statistic(observed, expected);
+
+    // Perform chi-squared test
+    chi_square_p_value(chi_squared_stat, observed.len() as f64 - 1.0)
+}
+
+/// Probability of a chi-square statistic of at least `statistic` with `degrees_of_freedom`.
+pub fn chi_square_p_value(statistic: f64, degrees_of_freedom: f64) -> f64 {
+    let chi_squared_dist = ChiSquared::new(degrees_of_freedom).expect("Failed to create ChiSquared distribution");
+    1.0 - chi_squared_dist.cdf(statistic)
+}
+
+/// Regularized upper incomplete gamma function Q(a, x), `igamc` of the NIST reference code, which gives the
+/// p-value of a chi-square statistic 2x with 2a degrees of freedom. A perfect fit, x of zero or rounded below
+/// it, has p-value 1.
+pub fn igamc(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    gamma_ur(a, x)
+}
+
+/// Pearson's chi-square statistic of observed counts, with `expected` rescaled to the same total.
+pub fn chi_square_statistic(observed: &[u64], expected: &[f64]) -> f64 {
     // Preconditions
     if observed.len() != expected.len() || observed.len() < 2 {
         panic!("Observed and expected arrays must have the same length and length >= 2.");
@@ -43,16 +70,9 @@ pub fn chi_square_test(observed: &[u64], expected: &[f64]) -> f64 {
     let rescaled_expected: Vec<f64> = expected.iter().map(|&e| e * sum_observed / sum_expected).collect();
 
     // Calculate chi-squared statistic
-    let chi_squared_stat: f64 = observed
+    observed
         .iter()
         .zip(rescaled_expected.iter())
         .map(|(&o, &e)| (o as f64 - e).powi(2) / e)
-        .sum();
-
-    // Perform chi-squared test
-    let degrees_of_freedom = observed.len() as f64 - 1.0;
-    let chi_squared_dist = ChiSquared::new(degrees_of_freedom).expect("Failed to create ChiSquared distribution");
-    let p_value = 1.0 - chi_squared_dist.cdf(chi_squared_stat);
-
-    p_value
-}
\ No newline at end of file
+        .sum()
+}
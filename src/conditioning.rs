@@ -0,0 +1,257 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::fmt;
+use std::str::FromStr;
+
+use aes::Aes128;
+use aes::cipher::{BlockEncrypt, KeyInit, generic_array::GenericArray};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::Onod;
+
+/// Vetted conditioning components of NIST SP 800-90B section 3.1.5.1.1.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditioningFunction {
+    Sha256,
+    HmacSha256,
+    #[serde(rename = "cbc_mac")]
+    AesCbcMac,
+}
+
+impl ConditioningFunction {
+    /// Output width nw of the underlying primitive in bits.
+    pub fn width(&self) -> usize {
+        match self {
+            ConditioningFunction::Sha256 | ConditioningFunction::HmacSha256 => 256,
+            ConditioningFunction::AesCbcMac => 128,
+        }
+    }
+
+    /// Input block size used when none is given: two output widths.
+    fn default_input_bits(&self) -> usize {
+        2 * self.width()
+    }
+}
+
+impl FromStr for ConditioningFunction {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "sha256" => Ok(ConditioningFunction::Sha256),
+            "hmac_sha256" => Ok(ConditioningFunction::HmacSha256),
+            "cbc_mac" | "aes_cbc_mac" => Ok(ConditioningFunction::AesCbcMac),
+            _ => Err(format!("Unknown conditioning function '{}'", name)),
+        }
+    }
+}
+
+impl fmt::Display for ConditioningFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConditioningFunction::Sha256 => write!(f, "sha256"),
+            ConditioningFunction::HmacSha256 => write!(f, "hmac_sha256"),
+            ConditioningFunction::AesCbcMac => write!(f, "cbc_mac"),
+        }
+    }
+}
+
+/// Compresses every block of `input_bits` into `output_bits` with a vetted function.
+/// Block sizes are whole bytes; an incomplete final block is dropped.
+pub struct Conditioner {
+    function: ConditioningFunction,
+    input_bits: usize,
+    output_bits: usize,
+    key: Vec<u8>,
+    pending: Vec<u8>,
+    blocks: u64,
+}
+
+impl Conditioner {
+    /// Creates a conditioner. HMAC-SHA-256 accepts any key; AES-CBC-MAC needs a 16 byte key
+    /// and whole 128-bit input blocks. An empty key selects an all-zero key.
+    pub fn new(function: ConditioningFunction, input_bits: usize, output_bits: usize, key: &[u8]) -> Result<Self, String> {
+        if input_bits == 0 || !input_bits.is_multiple_of(8) || output_bits == 0 || !output_bits.is_multiple_of(8) {
+            return Err("Conditioning block sizes must be positive multiples of 8 bits".to_string());
+        }
+        if output_bits > function.width() {
+            return Err(format!("{} outputs at most {} bits", function, function.width()));
+        }
+        if function == ConditioningFunction::Sha256 && !key.is_empty() {
+            return Err("sha256 does not take a key".to_string());
+        }
+
+        let key = match function {
+            ConditioningFunction::AesCbcMac => {
+                if !input_bits.is_multiple_of(128) {
+                    return Err("cbc_mac needs an input size that is a multiple of 128 bits".to_string());
+                }
+                match key.len() {
+                    0 => vec![0u8; 16],
+                    16 => key.to_vec(),
+                    _ => return Err("cbc_mac needs a 128-bit key".to_string()),
+                }
+            }
+            _ => key.to_vec(),
+        };
+
+        Ok(Conditioner {
+            function,
+            input_bits,
+            output_bits,
+            key,
+            pending: Vec::with_capacity(input_bits / 8),
+            blocks: 0,
+        })
+    }
+
+    /// Parses `function[:n_in[:n_out]]`, e.g. `sha256:512:256` or `cbc_mac`.
+    pub fn from_spec(spec: &str, key: &[u8]) -> Result<Self, String> {
+        let mut parts = spec.split(':');
+        let function: ConditioningFunction = parts.next().unwrap_or_default().parse()?;
+        let mut size = |default: usize| -> Result<usize, String> {
+            match parts.next() {
+                Some(bits) => bits.parse().map_err(|_| format!("Invalid block size in '{}'", spec)),
+                None => Ok(default),
+            }
+        };
+        let input_bits = size(function.default_input_bits())?;
+        let output_bits = size(function.width())?;
+        if parts.next().is_some() {
+            return Err(format!("Too many parameters in '{}'", spec));
+        }
+        Conditioner::new(function, input_bits, output_bits, key)
+    }
+
+    pub fn function(&self) -> ConditioningFunction {
+        self.function
+    }
+
+    pub fn input_bits(&self) -> usize {
+        self.input_bits
+    }
+
+    pub fn output_bits(&self) -> usize {
+        self.output_bits
+    }
+
+    /// Number of blocks conditioned so far.
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    /// Conditions one block of exactly `input_bits / 8` bytes.
+    pub fn condition_block(&self, block: &[u8]) -> Vec<u8> {
+        let mut output = match self.function {
+            ConditioningFunction::Sha256 => Sha256::digest(block).to_vec(),
+            ConditioningFunction::HmacSha256 => {
+                let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.key)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(block);
+                mac.finalize().into_bytes().to_vec()
+            }
+            ConditioningFunction::AesCbcMac => {
+                let cipher = Aes128::new(GenericArray::from_slice(&self.key));
+                let mut state = GenericArray::from([0u8; 16]);
+                for chunk in block.chunks_exact(16) {
+                    for (s, b) in state.iter_mut().zip(chunk) {
+                        *s ^= b;
+                    }
+                    cipher.encrypt_block(&mut state);
+                }
+                state.to_vec()
+            }
+        };
+        output.truncate(self.output_bits / 8);
+        output
+    }
+
+    /// Conditions the next chunk of input and appends the output to `out`.
+    pub fn process(&mut self, mut chunk: &[u8], out: &mut Vec<u8>) {
+        let block_len = self.input_bits / 8;
+        while !chunk.is_empty() {
+            let needed = (block_len - self.pending.len()).min(chunk.len());
+            self.pending.extend_from_slice(&chunk[..needed]);
+            chunk = &chunk[needed..];
+            if self.pending.len() == block_len {
+                out.extend_from_slice(&self.condition_block(&self.pending));
+                self.pending.clear();
+                self.blocks += 1;
+            }
+        }
+    }
+
+    /// Entropy of each output block for an input block carrying `h_in` bits of min-entropy.
+    pub fn output_entropy(&self, h_in: f64) -> f64 {
+        output_entropy(self.input_bits, self.output_bits, self.function.width(), h_in)
+    }
+}
+
+/// Output_Entropy(n_in, n_out, nw, h_in) of NIST SP 800-90B section 3.1.5.1.2.
+///
+/// Evaluated without forming 2^n_in so that large input blocks do not overflow:
+/// 2^(n_in - n) * P_low is rewritten as (1 - P_high) * 2^-n / (1 - 2^-n_in).
+pub fn output_entropy(n_in: usize, n_out: usize, nw: usize, h_in: f64) -> f64 {
+    let h_in = h_in.clamp(0.0, n_in as f64);
+    let n = n_out.min(nw) as f64;
+    let n_in = n_in as f64;
+
+    let p_high = 2f64.powf(-h_in);
+    let scale = (1.0 - p_high) / (1.0 - 2f64.powf(-n_in));
+
+    // 2^(n_in - n) * P_low and 2^((n_in - n) / 2) * P_low
+    let spread = scale * 2f64.powf(-n);
+    let spread_sqrt = scale * 2f64.powf(-(n_in + n) / 2.0);
+
+    let psi = spread + p_high;
+    let omega = spread + (2.0 * n * std::f64::consts::LN_2).sqrt() * spread_sqrt;
+
+    -psi.max(omega).log2()
+}
+
+/// Decodes a hexadecimal key.
+pub fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.trim();
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return Err(format!("Invalid hexadecimal key '{}'", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("Invalid hexadecimal key '{}'", hex)))
+        .collect()
+}
+
+impl Onod {
+    /// Conditions `samples` block by block and returns the conditioned bytes.
+    pub fn condition(samples: &[u8], conditioner: &mut Conditioner) -> Vec<u8> {
+        let mut out = Vec::new();
+        conditioner.process(samples, &mut out);
+        out
+    }
+}
@@ -0,0 +1,204 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use serde::Serialize;
+
+use crate::Onod;
+use crate::conditioning::{Conditioner, ConditioningFunction};
+use crate::entropy::estimators::{MinEntropyReport, assess, assess_iid};
+use crate::entropy::iid::{IidReport, PERMUTATIONS, iid_tests};
+
+/// Entropy claim for the output of a conditioning component (SP 800-90B section 3.1.5).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConditioningClaim {
+    pub function: ConditioningFunction,
+    /// n_in, n_out and the output width nw of the function, in bits.
+    #[serde(rename = "n_in")]
+    pub input_bits: usize,
+    #[serde(rename = "n_out")]
+    pub output_bits: usize,
+    #[serde(rename = "nw")]
+    pub width: usize,
+    /// Min-entropy of each input block, h_in, and of each output block, h_out.
+    pub h_in: f64,
+    pub h_out: f64,
+}
+
+impl ConditioningClaim {
+    /// The claim for `conditioner` fed with samples of `bits_per_symbol` bits carrying `h_min` bits each.
+    pub fn new(conditioner: &Conditioner, h_min: f64, bits_per_symbol: usize) -> Self {
+        let h_in = h_min * (conditioner.input_bits() / bits_per_symbol) as f64;
+        ConditioningClaim {
+            function: conditioner.function(),
+            input_bits: conditioner.input_bits(),
+            output_bits: conditioner.output_bits(),
+            width: conditioner.function().width(),
+            h_in,
+            h_out: conditioner.output_entropy(h_in),
+        }
+    }
+}
+
+/// Entropy assessment of a noise source from its raw samples: the IID determination, the estimates of the
+/// track it selects, the min-entropy per sample and, for a declared conditioner, the entropy of its output.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Assessment {
+    pub samples: usize,
+    pub bits_per_symbol: usize,
+    /// Seed of the permutation test shuffles.
+    pub seed: u32,
+    #[serde(rename = "iid_tests")]
+    pub iid: IidReport,
+    /// Estimates of the IID track (most common value) if the samples passed the IID tests, otherwise of all
+    /// non-IID estimators.
+    pub estimates: MinEntropyReport,
+    pub conditioning: Option<ConditioningClaim>,
+}
+
+impl Assessment {
+    pub fn is_iid(&self) -> bool {
+        self.iid.is_iid()
+    }
+
+    /// Min-entropy per sample, H_min.
+    pub fn h_min(&self) -> f64 {
+        self.estimates.min_entropy()
+    }
+
+    /// The assessment as a JSON document, with the verdict and the claims derived from it. The bit string
+    /// estimates stay per bit.
+    pub fn to_json(&self) -> String {
+        let json = Json {
+            assessment: self,
+            iid: self.is_iid(),
+            track: if self.is_iid() { "iid" } else { "non_iid" },
+            h_original: self.estimates.h_original(),
+            h_per_bit: self.estimates.h_bitstring(),
+            h_min: self.h_min(),
+        };
+        serde_json::to_string_pretty(&json).expect("An assessment always serializes")
+    }
+}
+
+/// JSON form of an assessment. Non-finite numbers become null.
+#[derive(Serialize)]
+struct Json<'a> {
+    #[serde(flatten)]
+    assessment: &'a Assessment,
+    iid: bool,
+    track: &'static str,
+    h_original: Option<f64>,
+    h_per_bit: f64,
+    h_min: f64,
+}
+
+/// Assesses `samples`, symbols of `bits_per_symbol` bits: the IID tests with up to `permutations` shuffles
+/// drawn from `seed` decide between the IID and the non-IID track, whose estimates give H_min, and the claim
+/// for `conditioner` follows from it.
+pub fn assess_source(
+    samples: &[u8],
+    bits_per_symbol: usize,
+    permutations: usize,
+    seed: u32,
+    conditioner: Option<&Conditioner>,
+) -> Assessment {
+    let iid = iid_tests(samples, bits_per_symbol, permutations, seed);
+    let estimates = if iid.is_iid() { assess_iid(samples, bits_per_symbol) } else { assess(samples, bits_per_symbol) };
+    let conditioning = conditioner
+        .map(|conditioner| ConditioningClaim::new(conditioner, estimates.min_entropy(), bits_per_symbol));
+    Assessment { samples: samples.len(), bits_per_symbol, seed, iid, estimates, conditioning }
+}
+
+impl Onod {
+
+    /// Entropy assessment (NIST SP 800-90B) of raw noise source samples, symbols of `bits_per_symbol` bits:
+    /// the IID tests with 10,000 shuffles, the min-entropy estimates of the track they select and the output
+    /// entropy of an optional conditioning component.
+    pub fn entropy_assessment(
+        samples: &[u8],
+        bits_per_symbol: usize,
+        seed: u32,
+        conditioner: Option<&Conditioner>,
+    ) -> Assessment {
+        assess_source(samples, bits_per_symbol, PERMUTATIONS, seed, conditioner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::xorshift_bytes;
+
+    #[test]
+    fn conditioning_claim() {
+        // Output_Entropy of SP 800-90B 3.1.5.1.2, evaluated in 50-digit arithmetic
+        let sha256 = Conditioner::from_spec("sha256:512:256", &[]).unwrap();
+        let claim = ConditioningClaim::new(&sha256, 0.5, 1);
+        assert_eq!((claim.input_bits, claim.output_bits, claim.width), (512, 256, 256));
+        assert_eq!(claim.h_in, 256.0);
+        assert!((claim.h_out - 255.0).abs() < 1e-9, "h_out = {}", claim.h_out);
+
+        // 128 4-bit samples of 3.2 bits saturate the output
+        let claim = ConditioningClaim::new(&sha256, 3.2, 4);
+        assert!((claim.h_in - 409.6).abs() < 1e-9 && (claim.h_out - 256.0).abs() < 1e-9, "{:?}", claim);
+
+        // 16 bytes of 7.5 bits in a block as wide as the output lose a little
+        let cbc_mac = Conditioner::from_spec("cbc_mac:128:128", &[]).unwrap();
+        let claim = ConditioningClaim::new(&cbc_mac, 7.5, 8);
+        assert_eq!((claim.function, claim.width, claim.h_in), (ConditioningFunction::AesCbcMac, 128, 120.0));
+        assert!((claim.h_out - 119.99437545080612).abs() < 1e-9, "h_out = {}", claim.h_out);
+    }
+
+    #[test]
+    fn json_field_names() {
+        let samples = xorshift_bytes(2_000);
+        let conditioner = Conditioner::from_spec("sha256:512:256", &[]).unwrap();
+        let assessment = assess_source(&samples, 8, 100, 3, Some(&conditioner));
+        let json: serde_json::Value = serde_json::from_str(&assessment.to_json()).unwrap();
+
+        let keys = |value: &serde_json::Value| {
+            let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let expected = [
+            "bits_per_symbol", "conditioning", "estimates", "h_min", "h_original", "h_per_bit", "iid", "iid_tests",
+            "samples", "seed", "track",
+        ];
+        assert_eq!(keys(&json), expected);
+        assert_eq!(keys(&json["iid_tests"]), ["chi_square", "longest_repeated_substring", "permutation"]);
+        assert_eq!(keys(&json["iid_tests"]["permutation"]), ["shuffles", "tests"]);
+        let test = &json["iid_tests"]["permutation"]["tests"][0];
+        assert_eq!(keys(test), ["equal", "greater", "less", "name", "passed", "statistic"]);
+        assert_eq!(keys(&json["estimates"]), ["bits_per_symbol", "bitstring_per_bit", "original"]);
+        assert_eq!(keys(&json["conditioning"]), ["function", "h_in", "h_out", "n_in", "n_out", "nw"]);
+        assert_eq!(json["conditioning"]["function"], "sha256");
+
+        assert_eq!(json["iid"], assessment.is_iid());
+        assert_eq!(json["track"], if assessment.is_iid() { "iid" } else { "non_iid" });
+        assert_eq!(json["h_min"], assessment.h_min());
+        assert_eq!(json["h_per_bit"], assessment.estimates.h_bitstring());
+    }
+}
@@ -0,0 +1,276 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use serde::Serialize;
+
+use crate::chisquaretest::{chi_square_p_value, chi_square_statistic};
+use crate::entropy::suffix_array::{lcp_array, suffix_array};
+
+/// Significance level of the IID tests of SP 800-90B.
+pub const IID_ALPHA: f64 = 0.001;
+
+/// Number of subsets the goodness-of-fit tests compare.
+const SUBSETS: usize = 10;
+
+/// Smallest expected count of a bin.
+const MIN_EXPECTED: f64 = 5.0;
+
+/// Longest tuples of the binary independence test.
+const MAX_TUPLE_BITS: usize = 11;
+
+/// Outcome of one chi-square test of SP 800-90B section 5.2.1.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ChiSquareTest {
+    pub name: &'static str,
+    pub statistic: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+}
+
+impl ChiSquareTest {
+    fn new(name: &'static str, statistic: f64, degrees_of_freedom: usize) -> Self {
+        let p_value = chi_square_p_value(statistic, degrees_of_freedom as f64);
+        ChiSquareTest { name, statistic, degrees_of_freedom, p_value }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.p_value >= IID_ALPHA
+    }
+}
+
+/// Outcome of the longest repeated substring test of SP 800-90B section 5.2.2.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LrsTest {
+    /// Length of the longest repeated substring.
+    pub length: usize,
+    /// Probability that IID samples repeat a substring at least that long.
+    pub probability: f64,
+}
+
+impl LrsTest {
+    pub fn passed(&self) -> bool {
+        self.probability >= IID_ALPHA
+    }
+}
+
+/// Assigns items to bins in increasing order of their expected counts, closing a bin once it expects at least 5
+/// and merging a short last bin into the one before. Returns the bin of every item and the number of bins.
+fn bins(expected: &[f64]) -> (Vec<usize>, usize) {
+    let mut order: Vec<usize> = (0..expected.len()).collect();
+    order.sort_by(|&a, &b| expected[a].total_cmp(&expected[b]));
+    let mut bin_of = vec![0; expected.len()];
+    let (mut bin, mut filled) = (0, 0.0);
+    let mut last_start = 0;
+    for (position, &item) in order.iter().enumerate() {
+        if filled == 0.0 {
+            last_start = position;
+        }
+        bin_of[item] = bin;
+        filled += expected[item];
+        if filled >= MIN_EXPECTED {
+            bin += 1;
+            filled = 0.0;
+        }
+    }
+    if filled > 0.0 {
+        if bin > 0 {
+            order[last_start..].iter().for_each(|&item| bin_of[item] = bin - 1);
+        } else {
+            bin += 1;
+        }
+    }
+    (bin_of, bin)
+}
+
+/// Observed and expected counts per bin.
+fn binned(bin_of: &[usize], bins: usize, observed: &[u64], expected: &[f64]) -> (Vec<u64>, Vec<f64>) {
+    let (mut o, mut e) = (vec![0; bins], vec![0.0; bins]);
+    for (item, &bin) in bin_of.iter().enumerate() {
+        o[bin] += observed[item];
+        e[bin] += expected[item];
+    }
+    (o, e)
+}
+
+/// Symbol counts of `symbols`.
+fn histogram(symbols: &[u8]) -> [u64; 256] {
+    let mut counts = [0; 256];
+    for &symbol in symbols {
+        counts[symbol as usize] += 1;
+    }
+    counts
+}
+
+/// Chi-square independence test of non-binary samples (SP 800-90B, 5.2.1.1): counts of overlapping pairs
+/// against the products of the symbol proportions. `None` when binning leaves no degrees of freedom.
+pub fn independence(symbols: &[u8]) -> Option<ChiSquareTest> {
+    let n = symbols.len();
+    let counts = histogram(symbols);
+    let present: Vec<usize> = (0..256).filter(|&symbol| counts[symbol] > 0).collect();
+    let k = present.len();
+    let mut index = [0; 256];
+    present.iter().enumerate().for_each(|(i, &symbol)| index[symbol] = i);
+
+    let p: Vec<f64> = present.iter().map(|&symbol| counts[symbol] as f64 / n as f64).collect();
+    let expected: Vec<f64> = (0..k * k).map(|pair| p[pair / k] * p[pair % k] * (n - 1) as f64).collect();
+    let mut observed = vec![0; k * k];
+    for pair in symbols.windows(2) {
+        observed[index[pair[0] as usize] * k + index[pair[1] as usize]] += 1;
+    }
+
+    let (bin_of, q) = bins(&expected);
+    if q <= k {
+        return None;
+    }
+    let (o, e) = binned(&bin_of, q, &observed, &expected);
+    Some(ChiSquareTest::new("independence", chi_square_statistic(&o, &e), q - k))
+}
+
+/// Chi-square goodness-of-fit test of non-binary samples (SP 800-90B, 5.2.1.2): the symbol counts of 10 equal
+/// subsets against a tenth of the overall counts. `None` for fewer than 10 samples or when binning leaves a single
+/// bin.
+pub fn goodness_of_fit(symbols: &[u8]) -> Option<ChiSquareTest> {
+    let length = symbols.len() / SUBSETS;
+    if length == 0 {
+        return None;
+    }
+    let counts = histogram(&symbols[..length * SUBSETS]);
+    let present: Vec<usize> = (0..256).filter(|&symbol| counts[symbol] > 0).collect();
+    let expected: Vec<f64> = present.iter().map(|&symbol| counts[symbol] as f64 / SUBSETS as f64).collect();
+
+    let (bin_of, q) = bins(&expected);
+    if q < 2 {
+        return None;
+    }
+    let statistic = symbols
+        .chunks_exact(length)
+        .take(SUBSETS)
+        .map(|subset| {
+            let counts = histogram(subset);
+            let observed: Vec<u64> = present.iter().map(|&symbol| counts[symbol]).collect();
+            let (o, e) = binned(&bin_of, q, &observed, &expected);
+            chi_square_statistic(&o, &e)
+        })
+        .sum();
+    Some(ChiSquareTest::new("goodness_of_fit", statistic, (SUBSETS - 1) * (q - 1)))
+}
+
+/// Chi-square independence test of binary samples (SP 800-90B, 5.2.1.3): counts of non-overlapping m-bit tuples
+/// against the probabilities their weights imply, for the longest m up to 11 at which the rarest tuple is still
+/// expected 5 times. `None` when not even 2-bit tuples are.
+pub fn binary_independence(bits: &[u8]) -> Option<ChiSquareTest> {
+    let n = bits.len();
+    let p1 = bits.iter().filter(|&&bit| bit == 1).count() as f64 / n as f64;
+    let p0 = 1.0 - p1;
+    let rarest = p0.min(p1);
+    let m = (2..=MAX_TUPLE_BITS).take_while(|&m| rarest.powi(m as i32) * (n / m) as f64 >= MIN_EXPECTED).last()?;
+
+    let mut observed = vec![0; 1 << m];
+    for tuple in bits.chunks_exact(m) {
+        observed[tuple.iter().fold(0, |value, &bit| (value << 1) | bit as usize)] += 1;
+    }
+    let expected: Vec<f64> = (0..1usize << m)
+        .map(|tuple| {
+            let weight = tuple.count_ones() as i32;
+            p1.powi(weight) * p0.powi(m as i32 - weight) * (n / m) as f64
+        })
+        .collect();
+    Some(ChiSquareTest::new("independence", chi_square_statistic(&observed, &expected), (1 << m) - 2))
+}
+
+/// Chi-square goodness-of-fit test of binary samples (SP 800-90B, 5.2.1.4): the counts of zeros and ones in 10
+/// equal subsets against the overall proportion. `None` for fewer than 10 bits or constant samples.
+pub fn binary_goodness_of_fit(bits: &[u8]) -> Option<ChiSquareTest> {
+    let length = bits.len() / SUBSETS;
+    if length == 0 {
+        return None;
+    }
+    let ones = bits[..length * SUBSETS].iter().filter(|&&bit| bit == 1).count() as f64;
+    let p1 = ones / (length * SUBSETS) as f64;
+    let expected = [(1.0 - p1) * length as f64, p1 * length as f64];
+    if expected.contains(&0.0) {
+        return None;
+    }
+    let statistic = bits
+        .chunks_exact(length)
+        .take(SUBSETS)
+        .map(|subset| {
+            let ones = subset.iter().filter(|&&bit| bit == 1).count() as u64;
+            chi_square_statistic(&[length as u64 - ones, ones], &expected)
+        })
+        .sum();
+    Some(ChiSquareTest::new("goodness_of_fit", statistic, SUBSETS - 1))
+}
+
+/// The chi-square tests that apply to `samples`, symbols of `bits_per_symbol` bits.
+pub fn chi_square_tests(samples: &[u8], bits_per_symbol: usize) -> Vec<ChiSquareTest> {
+    if samples.is_empty() {
+        return Vec::new();
+    }
+    if bits_per_symbol == 1 {
+        binary_independence(samples).into_iter().chain(binary_goodness_of_fit(samples)).collect()
+    } else {
+        independence(samples).into_iter().chain(goodness_of_fit(samples)).collect()
+    }
+}
+
+/// Longest repeated substring test (SP 800-90B, 5.2.2): whether IID samples with the observed symbol
+/// frequencies would plausibly repeat a substring as long as the longest one that repeats. Passes for no samples.
+pub fn lrs_test(symbols: &[u8]) -> LrsTest {
+    let n = symbols.len();
+    if n == 0 {
+        return LrsTest { length: 0, probability: 1.0 };
+    }
+    let sa = suffix_array(symbols);
+    let length = lcp_array(symbols, &sa).into_iter().max().unwrap_or(0) as usize;
+
+    let collision: f64 = histogram(symbols).iter().map(|&count| (count as f64 / n as f64).powi(2)).sum();
+    let tuples = (n - length + 1) as f64;
+    let pairs = tuples * (tuples - 1.0) / 2.0;
+    // 1 - (1 - p^W)^N, without losing a tiny p^W to rounding
+    let probability = -(pairs * (-collision.powi(length as i32)).ln_1p()).exp_m1();
+    LrsTest { length, probability }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_inputs_skip_goodness_of_fit() {
+        for n in [0, 1, 9] {
+            let bits: Vec<u8> = (0..n).map(|i| (i % 2) as u8).collect();
+            assert_eq!(binary_goodness_of_fit(&bits), None, "{} bits", n);
+            assert_eq!(goodness_of_fit(&bits), None, "{} samples", n);
+        }
+    }
+
+    #[test]
+    fn empty_input_passes() {
+        assert!(chi_square_tests(&[], 1).is_empty());
+        assert!(chi_square_tests(&[], 8).is_empty());
+        assert_eq!(lrs_test(&[]), LrsTest { length: 0, probability: 1.0 });
+    }
+}
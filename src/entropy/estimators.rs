@@ -0,0 +1,428 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use serde::Serialize;
+
+use crate::Onod;
+use crate::entropy::bitstring;
+use crate::entropy::predictors::predictors;
+use crate::entropy::suffix_array::tuple_repeats;
+
+/// Quantile of the standard normal distribution for the 99% confidence bounds of SP 800-90B.
+pub(crate) const Z_99: f64 = 2.576;
+
+/// One min-entropy estimate, in bits per sample of the data it was computed on.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Estimate {
+    pub name: &'static str,
+    pub entropy: f64,
+}
+
+/// Min-entropy estimates of SP 800-90B section 6.3 for one set of samples.
+///
+/// Binary samples are assessed by every estimator. Wider symbols are assessed by the estimators that apply to
+/// them and, as a bit string, by all estimators; the claim is the smaller of both, the bit string estimate
+/// scaled to the symbol width.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MinEntropyReport {
+    pub bits_per_symbol: usize,
+    /// Estimates on the symbols themselves, empty for binary samples.
+    pub original: Vec<Estimate>,
+    /// Estimates on the samples as a bit string, per bit.
+    #[serde(rename = "bitstring_per_bit")]
+    pub bitstring: Vec<Estimate>,
+}
+
+impl MinEntropyReport {
+    /// Smallest estimate on the symbols, capped at their width; `None` for binary samples.
+    pub fn h_original(&self) -> Option<f64> {
+        (!self.original.is_empty())
+            .then(|| self.original.iter().fold(self.bits_per_symbol as f64, |h, estimate| h.min(estimate.entropy)))
+    }
+
+    /// Smallest estimate on the bit string, per bit.
+    pub fn h_bitstring(&self) -> f64 {
+        self.bitstring.iter().fold(1.0, |h, estimate| h.min(estimate.entropy))
+    }
+
+    /// Min-entropy per sample: the minimum over all estimators.
+    pub fn min_entropy(&self) -> f64 {
+        let h_bitstring = self.bits_per_symbol as f64 * self.h_bitstring();
+        self.h_original().map_or(h_bitstring, |h| h.min(h_bitstring))
+    }
+}
+
+/// Solves `f(p) = target` for p in [lo, hi] by bisection, where `f` decreases.
+///
+/// Returns `None` when the target exceeds `f(lo)`, i.e. the samples look no less random than uniform ones.
+pub(crate) fn solve_decreasing(f: impl Fn(f64) -> f64, target: f64, lo: f64, hi: f64) -> Option<f64> {
+    if target >= f(lo) {
+        return None;
+    }
+    if target <= f(hi) {
+        return Some(hi);
+    }
+    let (mut lo, mut hi) = (lo, hi);
+    for _ in 0..64 {
+        let mid = (lo + hi) / 2.0;
+        if f(mid) > target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some((lo + hi) / 2.0)
+}
+
+/// Most common value estimate (SP 800-90B, 6.3.1): the upper 99% bound on the probability of the commonest symbol.
+pub fn most_common_value(symbols: &[u8]) -> Estimate {
+    let mut counts = [0usize; 256];
+    for &symbol in symbols {
+        counts[symbol as usize] += 1;
+    }
+    let p = *counts.iter().max().unwrap_or(&0) as f64 / symbols.len() as f64;
+    Estimate { name: "most_common_value", entropy: upper_bound_entropy(p, symbols.len()) }
+}
+
+/// Min-entropy of the upper 99% bound on a probability `p` estimated from `n` samples.
+fn upper_bound_entropy(p: f64, n: usize) -> f64 {
+    let p_u = f64::min(1.0, p + Z_99 * (p * (1.0 - p) / (n as f64 - 1.0)).sqrt());
+    -p_u.log2()
+}
+
+/// Collision estimate (SP 800-90B, 6.3.2) of binary samples, from the mean number of samples until a repeat.
+pub fn collision(bits: &[u8]) -> Estimate {
+    // Segments end at the first repeated value, which for bits is within 2 or 3 samples
+    let mut times = Vec::new();
+    let mut i = 0;
+    while i + 1 < bits.len() {
+        let t = if bits[i] == bits[i + 1] {
+            2
+        } else if i + 2 < bits.len() {
+            3
+        } else {
+            break;
+        };
+        times.push(t as f64);
+        i += t;
+    }
+    let v = times.len() as f64;
+    let mean = times.iter().sum::<f64>() / v;
+    let sigma = (times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (v - 1.0)).sqrt();
+    let bound = mean - Z_99 * sigma / v.sqrt();
+
+    // Expected collision time of a bit with probability p, using F(q) = Γ(3, 1/q) q³ e^(1/q)
+    let expected = |p: f64| {
+        let q = 1.0 - p;
+        let z = 1.0 / q;
+        let f = 2.0 * (1.0 + z + z * z / 2.0) / z.powi(3);
+        let d = 0.5 * (1.0 / p - 1.0 / q);
+        p / (q * q) * (1.0 + d) * f - p / q * d
+    };
+    let entropy = solve_decreasing(expected, bound, 0.5, 1.0).map_or(1.0, |p| -p.log2());
+    Estimate { name: "collision", entropy }
+}
+
+/// Markov estimate (SP 800-90B, 6.3.3) of binary samples, from the likeliest 128-bit sequence of a first order
+/// Markov model fitted to them.
+pub fn markov(bits: &[u8]) -> Estimate {
+    let n = bits.len() as f64;
+    let ones = bits.iter().filter(|&&bit| bit == 1).count() as f64;
+    let mut transitions = [[0.0f64; 2]; 2];
+    for pair in bits.windows(2) {
+        transitions[pair[0] as usize][pair[1] as usize] += 1.0;
+    }
+    let initial = [(n - ones) / n, ones / n];
+    let p = transitions.map(|row| {
+        let total = row[0] + row[1];
+        row.map(|count| if total > 0.0 { count / total } else { 0.0 })
+    });
+
+    // Log probabilities of the candidate sequences 0…0, 0101…, 01…1, 10…0, 1010… and 1…1
+    let candidates = [
+        initial[0].log2() + 127.0 * p[0][0].log2(),
+        initial[0].log2() + 64.0 * p[0][1].log2() + 63.0 * p[1][0].log2(),
+        initial[0].log2() + p[0][1].log2() + 126.0 * p[1][1].log2(),
+        initial[1].log2() + p[1][0].log2() + 126.0 * p[0][0].log2(),
+        initial[1].log2() + 64.0 * p[1][0].log2() + 63.0 * p[0][1].log2(),
+        initial[1].log2() + 127.0 * p[1][1].log2(),
+    ];
+    let log_max = candidates.iter().copied().filter(|c| !c.is_nan()).fold(f64::NEG_INFINITY, f64::max);
+    Estimate { name: "markov", entropy: f64::min(-log_max / 128.0, 1.0) }
+}
+
+/// Bits per block of the compression estimate.
+const COMPRESSION_BLOCK: u32 = 6;
+
+/// Blocks that initialize the dictionary of the compression estimate.
+const COMPRESSION_DICTIONARY: usize = 1000;
+
+/// Compression estimate (SP 800-90B, 6.3.4) of binary samples, from the mean log distance between repeats of
+/// 6-bit blocks in the manner of Maurer's universal statistic.
+pub fn compression(bits: &[u8]) -> Estimate {
+    let blocks: Vec<usize> = bits
+        .chunks_exact(COMPRESSION_BLOCK as usize)
+        .map(|block| block.iter().fold(0, |value, &bit| (value << 1) | bit as usize))
+        .collect();
+    let d = COMPRESSION_DICTIONARY;
+    if blocks.len() <= d + 1 {
+        return Estimate { name: "compression", entropy: 1.0 };
+    }
+
+    let mut last_seen = [0usize; 1 << COMPRESSION_BLOCK];
+    let (mut sum, mut sum_squares) = (0.0, 0.0);
+    for (i, &block) in blocks.iter().enumerate() {
+        let index = i + 1;
+        if index > d {
+            let distance = if last_seen[block] != 0 { index - last_seen[block] } else { index };
+            let log = (distance as f64).log2();
+            sum += log;
+            sum_squares += log * log;
+        }
+        last_seen[block] = index;
+    }
+    let nu = (blocks.len() - d) as f64;
+    let mean = sum / nu;
+    let sigma = 0.5907 * (sum_squares / (nu - 1.0) - mean * mean).max(0.0).sqrt();
+    let bound = mean - Z_99 * sigma / nu.sqrt();
+
+    let alphabet = ((1 << COMPRESSION_BLOCK) - 1) as f64;
+    let expected = |p: f64| {
+        let g = |z| compression_g(z, d, blocks.len());
+        g(p) + alphabet * g((1.0 - p) / alphabet)
+    };
+    let entropy = solve_decreasing(expected, bound, 1.0 / (alphabet + 1.0), 1.0)
+        .map_or(1.0, |p| -p.log2() / COMPRESSION_BLOCK as f64);
+    Estimate { name: "compression", entropy }
+}
+
+/// G(z) of the compression estimate: the mean over the test blocks t = d + 1..=blocks of the expected log2 distance
+/// to the previous occurrence of a value with probability z.
+fn compression_g(z: f64, d: usize, blocks: usize) -> f64 {
+    // Partial sums over u < t of log2(u) z² (1 - z)^(u - 1), extended one u at a time
+    let (mut inner, mut power, mut total) = (0.0, 1.0, 0.0);
+    for t in 1..=blocks {
+        let log_t = (t as f64).log2();
+        if t > d {
+            total += inner + log_t * z * power;
+        }
+        inner += log_t * z * z * power;
+        power *= 1.0 - z;
+        if power < f64::MIN_POSITIVE && t > d {
+            // Every later term only adds the converged inner sum
+            total += (blocks - t) as f64 * inner;
+            break;
+        }
+    }
+    total / (blocks - d) as f64
+}
+
+/// Occurrences of the most common tuple the t-tuple estimate requires of a tuple length.
+pub const TUPLE_CUTOFF: u64 = 35;
+
+/// Number of tuple lengths, from 1 on, whose most common tuple occurs at least `TUPLE_CUTOFF` times.
+fn common_lengths(most: &[u64]) -> usize {
+    most.iter().skip(1).take_while(|&&count| count >= TUPLE_CUTOFF).count()
+}
+
+/// t-tuple estimate from the most common tuple counts of `tuple_repeats` over `n` samples.
+fn t_tuple_estimate(n: usize, most: &[u64]) -> Estimate {
+    // Samples too few for any tuple to reach the cutoff still get the estimate of single symbols, each of which
+    // occurs at least once
+    let t = common_lengths(most).max(1);
+    let p = (1..=t.min(most.len() - 1))
+        .map(|i| (most[i] as f64 / (n - i + 1) as f64).powf(1.0 / i as f64))
+        .fold(1.0 / n as f64, f64::max);
+    Estimate { name: "t_tuple", entropy: upper_bound_entropy(p, n) }
+}
+
+/// LRS estimate from the counts of `tuple_repeats` over `n` samples, `None` when no tuple longer than the
+/// t-tuple estimate's repeats.
+fn lrs_estimate(n: usize, most: &[u64], pairs: &[u64]) -> Option<Estimate> {
+    let (u, v) = (common_lengths(most) + 1, most.len() - 1);
+    let p = (u..=v)
+        .map(|w| {
+            let tuples = (n - w + 1) as f64;
+            (pairs[w] as f64 / (tuples * (tuples - 1.0) / 2.0)).powf(1.0 / w as f64)
+        })
+        .reduce(f64::max)?;
+    Some(Estimate { name: "lrs", entropy: upper_bound_entropy(p, n) })
+}
+
+/// t-tuple estimate (SP 800-90B, 6.3.5): the most common tuple of every length that occurs at least 35 times.
+pub fn t_tuple(symbols: &[u8]) -> Estimate {
+    t_tuple_estimate(symbols.len(), &tuple_repeats(symbols).0)
+}
+
+/// Longest repeated substring estimate (SP 800-90B, 6.3.6): the collision probability of the tuples too long
+/// for the t-tuple estimate, up to the longest repeated one. `None` when there are none.
+pub fn lrs(symbols: &[u8]) -> Option<Estimate> {
+    let (most, pairs) = tuple_repeats(symbols);
+    lrs_estimate(symbols.len(), &most, &pairs)
+}
+
+/// The t-tuple and LRS estimates, sharing one suffix array.
+fn repeat_estimates(symbols: &[u8]) -> Vec<Estimate> {
+    let (most, pairs) = tuple_repeats(symbols);
+    let mut estimates = vec![t_tuple_estimate(symbols.len(), &most)];
+    estimates.extend(lrs_estimate(symbols.len(), &most, &pairs));
+    estimates
+}
+
+/// Runs the estimators over `samples`, symbols of `bits_per_symbol` bits, and collects their estimates.
+pub fn assess(samples: &[u8], bits_per_symbol: usize) -> MinEntropyReport {
+    let estimates = |symbols: &[u8], bits_per_symbol| {
+        let mut estimates = repeat_estimates(symbols);
+        estimates.extend(predictors(symbols, bits_per_symbol).iter().map(|predictor| predictor.estimate()));
+        estimates
+    };
+    let bits = if bits_per_symbol == 1 { samples.to_vec() } else { bitstring(samples, bits_per_symbol) };
+    let original = if bits_per_symbol == 1 {
+        Vec::new()
+    } else {
+        let mut original = vec![most_common_value(samples)];
+        original.extend(estimates(samples, bits_per_symbol));
+        original
+    };
+    let mut bitstring = vec![most_common_value(&bits), collision(&bits), markov(&bits), compression(&bits)];
+    bitstring.extend(estimates(&bits, 1));
+    MinEntropyReport { bits_per_symbol, original, bitstring }
+}
+
+/// The IID track of SP 800-90B section 6.1: only the most common value estimate, on the symbols and on the
+/// bit string, for samples that passed the IID tests.
+pub fn assess_iid(samples: &[u8], bits_per_symbol: usize) -> MinEntropyReport {
+    let original = if bits_per_symbol == 1 { Vec::new() } else { vec![most_common_value(samples)] };
+    let bits = if bits_per_symbol == 1 { samples.to_vec() } else { bitstring(samples, bits_per_symbol) };
+    MinEntropyReport { bits_per_symbol, original, bitstring: vec![most_common_value(&bits)] }
+}
+
+impl Onod {
+
+    /// Min-entropy per sample of `samples`, symbols of `bits_per_symbol` bits, as assessed by the non-IID
+    /// estimators of NIST SP 800-90B.
+    /// Unlike `Onod::shannon`, which measures the average information, this bounds the predictability of the
+    /// likeliest outcome, as entropy source certification requires.
+    pub fn min_entropy(samples: &[u8], bits_per_symbol: usize) -> MinEntropyReport {
+        assess(samples, bits_per_symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::E;
+
+    /// The first 20,000 bits of e.
+    fn e_bits() -> Vec<u8> {
+        bitstring(&E[..2_500], 8)
+    }
+
+    /// 10,000 biased 2-bit symbols, the nibbles of e capped at 3.
+    fn biased_symbols() -> Vec<u8> {
+        E[..5_000].iter().flat_map(|&byte| [(byte >> 4).min(3), (byte & 15).min(3)]).collect()
+    }
+
+    fn assert_entropy(estimate: Estimate, expected: f64) {
+        assert!((estimate.entropy - expected).abs() < 1e-9, "{}: {}", estimate.name, estimate.entropy);
+    }
+
+    #[test]
+    fn most_common_value_matches_sp_800_90b_example() {
+        // SP 800-90B 6.3.1: the commonest of 20 samples occurs 8 times, p_u = 0.4 + 2.576 sqrt(0.4 * 0.6 / 19)
+        let samples = [0, 1, 1, 2, 0, 1, 2, 2, 0, 1, 0, 1, 1, 0, 2, 2, 1, 0, 2, 1];
+        let p_u = 0.4 + Z_99 * (0.4f64 * 0.6 / 19.0).sqrt();
+        assert_entropy(most_common_value(&samples), -p_u.log2());
+        assert!((most_common_value(&samples).entropy - 0.536).abs() < 1e-3);
+    }
+
+    #[test]
+    fn markov_takes_the_likeliest_sequence() {
+        // 0001 repeated: P(0) = 3/4, a 0 is followed by 0 with probability 2/3 and a 1 always by 0, so 0…0 is the
+        // likeliest 128-bit sequence
+        let bits: Vec<u8> = [0, 0, 0, 1].repeat(250);
+        assert_entropy(markov(&bits), -(0.75f64.log2() + 127.0 * (2.0f64 / 3.0).log2()) / 128.0);
+        assert_entropy(markov(&[1; 100]), 0.0);
+    }
+
+    #[test]
+    fn estimates_match_reference() {
+        // Computed by a straightforward transcription of SP 800-90B 6.3.1 to 6.3.4: the collision estimate with
+        // F(q) from the incomplete gamma function, the Markov estimate by dynamic programming over all 128-bit
+        // sequences and the compression estimate summing G(z) term by term
+        let bits = e_bits();
+        assert_entropy(most_common_value(&bits), 0.9701365960252436);
+        assert_entropy(collision(&bits), 0.7879860965573694);
+        assert_entropy(markov(&bits), 0.9857632203623274);
+        assert_entropy(compression(&bits), 0.6509770012776515);
+
+        let symbols = biased_symbols();
+        assert_entropy(most_common_value(&symbols), 0.28284708454807694);
+        let bits = bitstring(&symbols, 2);
+        assert_entropy(most_common_value(&bits), 0.18314740279750075);
+        assert_entropy(collision(&bits), 0.11598302483166606);
+        assert_entropy(markov(&bits), 0.15056115991017557);
+        assert_entropy(compression(&bits), 0.09203623374837372);
+    }
+
+    #[test]
+    fn repeat_estimates_match_reference() {
+        // Computed from the tuple counts of every length, counted one window at a time
+        let expected = [(0.8912434174973193, 0.9494906018548099), (0.14074634879480835, 0.27939849330304967)];
+        for (bits, (t_tuple_entropy, lrs_entropy)) in [e_bits(), bitstring(&biased_symbols(), 2)].iter().zip(expected) {
+            assert_entropy(t_tuple(bits), t_tuple_entropy);
+            assert_entropy(lrs(bits).unwrap(), lrs_entropy);
+        }
+        assert_entropy(t_tuple(&biased_symbols()), 0.28158072915553256);
+        assert_entropy(lrs(&biased_symbols()).unwrap(), 0.555579247203672);
+    }
+
+    #[test]
+    fn repeat_estimates_of_short_inputs() {
+        // No tuple of a 7-symbol cycle of 203 samples occurs 35 times, so the t-tuple estimate falls back to
+        // single symbols, each of which occurs 29 times
+        let cycle: Vec<u8> = (0..7).cycle().take(203).collect();
+        let p = 1.0f64 / 7.0;
+        assert_entropy(t_tuple(&cycle), -(p + Z_99 * (p * (1.0 - p) / 202.0).sqrt()).log2());
+        assert_eq!(lrs(b"abcdef"), None);
+    }
+
+    #[test]
+    fn min_entropy_is_the_smallest_estimate() {
+        let estimate = |entropy| Estimate { name: "most_common_value", entropy };
+        let report = MinEntropyReport {
+            bits_per_symbol: 4,
+            original: vec![estimate(3.1), estimate(2.9)],
+            bitstring: vec![estimate(0.9), estimate(0.8)],
+        };
+        assert_eq!(report.h_original(), Some(2.9));
+        assert_eq!(report.h_bitstring(), 0.8);
+        assert_eq!(report.min_entropy(), 2.9);
+
+        let report = MinEntropyReport { bits_per_symbol: 4, original: vec![estimate(4.5)], ..report };
+        assert_eq!(report.h_original(), Some(4.0));
+        assert!((report.min_entropy() - 3.2).abs() < 1e-12);
+    }
+}
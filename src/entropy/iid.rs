@@ -0,0 +1,439 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::thread;
+
+use serde::{Serialize, Serializer};
+
+use crate::Onod;
+use crate::entropy::chi_square::{ChiSquareTest, LrsTest, chi_square_tests, lrs_test};
+use crate::entropy::{hamming_weights, pack_bits};
+use crate::uniformity::compression::bzipped_len;
+use crate::well19937c::Well19937c;
+
+/// Number of shuffles SP 800-90B prescribes.
+pub const PERMUTATIONS: usize = 10_000;
+
+/// Shuffles done between checks whether every statistic is decided.
+const ROUND: usize = 100;
+
+/// Lags of the periodicity and covariance statistics.
+const LAGS: [usize; 5] = [1, 2, 8, 16, 32];
+
+/// Names of the permutation test statistics of SP 800-90B section 5.1, in the order they are computed.
+pub const STATISTICS: [&str; 19] = [
+    "excursion",
+    "directional_runs",
+    "longest_directional_run",
+    "increases_decreases",
+    "median_runs",
+    "longest_median_run",
+    "average_collision",
+    "maximum_collision",
+    "periodicity_1",
+    "periodicity_2",
+    "periodicity_8",
+    "periodicity_16",
+    "periodicity_32",
+    "covariance_1",
+    "covariance_2",
+    "covariance_8",
+    "covariance_16",
+    "covariance_32",
+    "compression",
+];
+
+/// Outcome of one statistic: its value on the original samples and how many shuffles ranked above, level with
+/// or below it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PermutationTest {
+    pub name: &'static str,
+    pub statistic: f64,
+    pub greater: u32,
+    pub equal: u32,
+    pub less: u32,
+}
+
+impl PermutationTest {
+    /// The original ranks neither among the lowest nor among the highest 5 statistics.
+    pub fn passed(&self) -> bool {
+        self.greater + self.equal > 5 && self.equal + self.less > 5
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PermutationReport {
+    /// Number of shuffles performed, at most the number requested.
+    pub shuffles: usize,
+    #[serde(serialize_with = "with_verdicts")]
+    pub tests: Vec<PermutationTest>,
+}
+
+impl PermutationReport {
+    /// Whether the IID assumption stands, i.e. every statistic passed.
+    pub fn is_iid(&self) -> bool {
+        self.tests.iter().all(PermutationTest::passed)
+    }
+}
+
+/// Outcome of all IID tests of SP 800-90B section 5.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct IidReport {
+    pub permutation: PermutationReport,
+    /// The chi-square tests that apply to the samples.
+    #[serde(serialize_with = "with_verdicts")]
+    pub chi_square: Vec<ChiSquareTest>,
+    #[serde(rename = "longest_repeated_substring", serialize_with = "with_verdict")]
+    pub lrs: LrsTest,
+}
+
+impl IidReport {
+    /// Whether the samples may be treated as IID, i.e. every test passed.
+    pub fn is_iid(&self) -> bool {
+        self.permutation.is_iid() && self.chi_square.iter().all(ChiSquareTest::passed) && self.lrs.passed()
+    }
+}
+
+/// Outcome of a test that passes or fails.
+trait Verdict: Serialize {
+    fn passed(&self) -> bool;
+}
+
+impl Verdict for PermutationTest {
+    fn passed(&self) -> bool {
+        PermutationTest::passed(self)
+    }
+}
+
+impl Verdict for ChiSquareTest {
+    fn passed(&self) -> bool {
+        ChiSquareTest::passed(self)
+    }
+}
+
+impl Verdict for LrsTest {
+    fn passed(&self) -> bool {
+        LrsTest::passed(self)
+    }
+}
+
+/// A test serialized together with whether it passed.
+#[derive(Serialize)]
+struct WithVerdict<'a, T> {
+    #[serde(flatten)]
+    test: &'a T,
+    passed: bool,
+}
+
+fn with_verdict<T: Verdict, S: Serializer>(test: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    WithVerdict { test, passed: test.passed() }.serialize(serializer)
+}
+
+fn with_verdicts<T: Verdict, S: Serializer>(tests: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(tests.iter().map(|test| WithVerdict { test, passed: test.passed() }))
+}
+
+/// Properties of the samples that shuffling does not change.
+struct Invariants {
+    binary: bool,
+    mean: f64,
+    median: f64,
+}
+
+impl Invariants {
+    fn new(samples: &[u8], binary: bool) -> Self {
+        let mut histogram = [0usize; 256];
+        for &sample in samples {
+            histogram[sample as usize] += 1;
+        }
+        // Average of the two middle values, which coincide for an odd number of samples
+        let nth = |rank: usize| {
+            let mut seen = 0;
+            (0..256).find(|&value| {
+                seen += histogram[value];
+                seen > rank
+            }).unwrap_or(0) as f64
+        };
+        let n = samples.len();
+        let median = match (binary, n) {
+            (true, _) => 0.5,
+            (false, 0) => 0.0,
+            (false, n) => (nth((n - 1) / 2) + nth(n / 2)) / 2.0,
+        };
+        let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / n.max(1) as f64;
+        Invariants { binary, mean, median }
+    }
+}
+
+/// Number of runs and length of the longest run in a sequence of signs.
+fn runs(signs: impl Iterator<Item = bool>) -> (usize, usize) {
+    let (mut count, mut longest, mut length, mut last) = (0, 0, 0, None);
+    for sign in signs {
+        if Some(sign) == last {
+            length += 1;
+        } else {
+            count += 1;
+            length = 1;
+            last = Some(sign);
+        }
+        longest = longest.max(length);
+    }
+    (count, longest)
+}
+
+/// Lengths of the consecutive segments that each end with the first repeated value.
+fn collisions(samples: &[u8]) -> (usize, usize, usize) {
+    let (mut total, mut count, mut longest) = (0, 0, 0);
+    let mut seen = [usize::MAX; 256];
+    let mut start = 0;
+    for (j, &sample) in samples.iter().enumerate() {
+        if seen[sample as usize] != usize::MAX && seen[sample as usize] >= start {
+            let length = j - start + 1;
+            total += length;
+            count += 1;
+            longest = longest.max(length);
+            start = j + 1;
+        } else {
+            seen[sample as usize] = j;
+        }
+    }
+    (total, count, longest)
+}
+
+/// All statistics of SP 800-90B section 5.1 over `samples`.
+///
+/// For binary samples the directional, periodicity and covariance statistics use the Hamming weights of
+/// bytes (conversion I) and the collision statistics the bytes themselves (conversion II).
+fn statistics(samples: &[u8], invariants: &Invariants, text: &mut Vec<u8>, compress: bool) -> [f64; 19] {
+    let mut t = [0.0; 19];
+
+    // Excursion: largest distance of a partial sum from its expectation
+    let mut sum = 0.0;
+    for (i, &sample) in samples.iter().enumerate() {
+        sum += sample as f64;
+        t[0] = f64::max(t[0], (sum - (i + 1) as f64 * invariants.mean).abs());
+    }
+
+    let weights;
+    let converted = if invariants.binary {
+        weights = hamming_weights(samples);
+        &weights
+    } else {
+        samples
+    };
+
+    // Directional runs, longest directional run, and increases versus decreases
+    let increases = converted.windows(2).map(|pair| pair[0] <= pair[1]);
+    let (count, longest) = runs(increases.clone());
+    let up = increases.filter(|&up| up).count();
+    t[1] = count as f64;
+    t[2] = longest as f64;
+    t[3] = up.max(converted.len().saturating_sub(1) - up) as f64;
+
+    // Runs above and below the median
+    let (count, longest) = runs(samples.iter().map(|&sample| sample as f64 >= invariants.median));
+    t[4] = count as f64;
+    t[5] = longest as f64;
+
+    // Average and maximum collision
+    let packed;
+    let collided = if invariants.binary {
+        packed = pack_bits(samples);
+        &packed
+    } else {
+        samples
+    };
+    let (total, count, longest) = collisions(collided);
+    t[6] = if count > 0 { total as f64 / count as f64 } else { 0.0 };
+    t[7] = longest as f64;
+
+    // Periodicity and covariance
+    for (k, &lag) in LAGS.iter().enumerate() {
+        let pairs = converted.iter().zip(converted.iter().skip(lag));
+        t[8 + k] = pairs.clone().filter(|(a, b)| a == b).count() as f64;
+        t[13 + k] = pairs.map(|(&a, &b)| a as u64 * b as u64).sum::<u64>() as f64;
+    }
+
+    // bzip2 compression of the samples written as decimal numbers separated by spaces, by far the slowest statistic
+    if !compress {
+        return t;
+    }
+    text.clear();
+    for (i, &sample) in samples.iter().enumerate() {
+        if i > 0 {
+            text.push(b' ');
+        }
+        text.extend_from_slice(sample.to_string().as_bytes());
+    }
+    t[18] = bzipped_len(text) as f64;
+
+    t
+}
+
+/// Runs the permutation tests of SP 800-90B section 5.1 over `samples`, symbols of `bits_per_symbol` bits.
+///
+/// Every statistic of the original samples is ranked among those of up to `permutations` Fisher-Yates shuffles.
+/// Like the NIST tool, a statistic is no longer ranked once more than 5 shuffles fell on either side of it, and
+/// shuffling stops when that holds for all of them. Shuffle i draws from a Well19937c generator seeded from `seed`
+/// and i, and shuffles are done in rounds of fixed size, so the report depends on the seed only, not on the number
+/// of threads the shuffles are spread over.
+pub fn permutation_tests(samples: &[u8], bits_per_symbol: usize, permutations: usize, seed: u32) -> PermutationReport {
+    let invariants = Invariants::new(samples, bits_per_symbol == 1);
+    let original = statistics(samples, &invariants, &mut Vec::new(), true);
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+    let mut counts = [[0u32; 3]; 19];
+    let mut shuffles = 0;
+    while shuffles < permutations {
+        let decided = counts.map(|[greater, equal, less]| greater + equal > 5 && equal + less > 5);
+        if decided.iter().all(|&decided| decided) {
+            break;
+        }
+        let round = shuffles..(shuffles + ROUND).min(permutations);
+        let rounds: Vec<[[u32; 3]; 19]> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.min(round.len()))
+                .map(|worker| {
+                    let (invariants, original, round) = (&invariants, &original, round.clone());
+                    scope.spawn(move || {
+                        let mut counts = [[0u32; 3]; 19];
+                        let mut shuffled = samples.to_vec();
+                        let mut text = Vec::new();
+                        for i in round.skip(worker).step_by(threads) {
+                            let mut rng = Well19937c::new(seed.wrapping_add((i as u32).wrapping_mul(0x9e37_79b9)));
+                            shuffled.copy_from_slice(samples);
+                            for k in (1..shuffled.len()).rev() {
+                                shuffled.swap(k, rng.next_below(k as u32 + 1) as usize);
+                            }
+                            let t = statistics(&shuffled, invariants, &mut text, !decided[18]);
+                            for (j, count) in counts.iter_mut().enumerate().filter(|&(j, _)| !decided[j]) {
+                                count[(t[j] <= original[j]) as usize + (t[j] < original[j]) as usize] += 1;
+                            }
+                        }
+                        counts
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|worker| worker.join().expect("Permutation worker panicked")).collect()
+        });
+        for round in rounds {
+            for (count, round) in counts.iter_mut().zip(round) {
+                count.iter_mut().zip(round).for_each(|(count, round)| *count += round);
+            }
+        }
+        shuffles = round.end;
+    }
+
+    let tests = STATISTICS
+        .iter()
+        .zip(original)
+        .zip(counts)
+        .map(|((&name, statistic), [greater, equal, less])| PermutationTest { name, statistic, greater, equal, less })
+        .collect();
+
+    PermutationReport { shuffles, tests }
+}
+
+/// Runs every IID test of SP 800-90B over `samples`: the permutation tests with up to `permutations` shuffles,
+/// the chi-square tests and the longest repeated substring test.
+pub fn iid_tests(samples: &[u8], bits_per_symbol: usize, permutations: usize, seed: u32) -> IidReport {
+    IidReport {
+        permutation: permutation_tests(samples, bits_per_symbol, permutations, seed),
+        chi_square: chi_square_tests(samples, bits_per_symbol),
+        lrs: lrs_test(samples),
+    }
+}
+
+impl Onod {
+
+    /// IID permutation testing (NIST SP 800-90B, 5.1) with 10,000 shuffles.
+    /// Decides whether `samples`, symbols of `bits_per_symbol` bits, may be treated as independent and identically
+    /// distributed. The shuffles are reproducible for a given `seed`.
+    pub fn iid_permutation_tests(samples: &[u8], bits_per_symbol: usize, seed: u32) -> PermutationReport {
+        permutation_tests(samples, bits_per_symbol, PERMUTATIONS, seed)
+    }
+
+    /// IID verdict of NIST SP 800-90B section 5: the permutation tests with 10,000 shuffles, the chi-square
+    /// independence and goodness-of-fit tests and the longest repeated substring test.
+    pub fn iid_tests(samples: &[u8], bits_per_symbol: usize, seed: u32) -> IidReport {
+        iid_tests(samples, bits_per_symbol, PERMUTATIONS, seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entropy::bitstring;
+    use crate::stream::testing::xorshift_bytes;
+
+    fn statistic(name: &str, samples: &[u8]) -> f64 {
+        let t = statistics(samples, &Invariants::new(samples, false), &mut Vec::new(), true);
+        t[STATISTICS.iter().position(|&statistic| statistic == name).unwrap()]
+    }
+
+    #[test]
+    fn statistics_match_sp_800_90b_examples() {
+        // The examples of SP 800-90B sections 5.1.1 to 5.1.10
+        assert_eq!(statistic("excursion", &[2, 15, 4, 10, 9]), 6.0);
+        let directional = [2, 2, 2, 5, 7, 7, 9, 3, 1, 4, 4];
+        assert_eq!(statistic("directional_runs", &directional), 3.0);
+        assert_eq!(statistic("longest_directional_run", &directional), 6.0);
+        assert_eq!(statistic("increases_decreases", &directional), 8.0);
+        let median = [5, 15, 12, 1, 13, 9, 4];
+        assert_eq!(statistic("median_runs", &median), 5.0);
+        assert_eq!(statistic("longest_median_run", &median), 2.0);
+        let collision = [2, 1, 1, 2, 0, 1, 0, 1, 1, 2];
+        assert_eq!(statistic("average_collision", &collision), 3.0);
+        assert_eq!(statistic("maximum_collision", &collision), 4.0);
+        assert_eq!(statistic("periodicity_2", &[2, 1, 2, 1, 0, 1, 0, 1, 1, 2]), 5.0);
+        assert_eq!(statistic("covariance_2", &[5, 2, 6, 10, 12, 3, 1]), 164.0);
+
+        // 5.1.11: the length of the bzip2 compressed decimal encoding
+        let compressed = bzipped_len(b"144 21 139 0 0 15") as f64;
+        assert_eq!(statistic("compression", &[144, 21, 139, 0, 0, 15]), compressed);
+    }
+
+    #[test]
+    fn binary_samples_are_converted() {
+        // Directional and covariance statistics see the Hamming weights (3, 5, 8), collisions the packed bytes
+        let bits = bitstring(&[0b0100_1001, 0b1101_0110, 0xff], 8);
+        let t = statistics(&bits, &Invariants::new(&bits, true), &mut Vec::new(), false);
+        assert_eq!(t[3], 2.0);
+        assert_eq!(t[13], (3 * 5 + 5 * 8) as f64);
+        assert_eq!((t[6], t[7]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn rejects_sorted_samples_and_is_reproducible() {
+        let sorted: Vec<u8> = (0..=255).flat_map(|value| [value; 4]).collect();
+        let report = permutation_tests(&sorted, 8, 200, 1);
+        assert!(!report.is_iid());
+        assert_eq!(report.shuffles, 200); // The failing statistics are never decided
+
+        let samples = xorshift_bytes(2_000);
+        let report = permutation_tests(&samples, 8, 200, 7);
+        assert_eq!(report, permutation_tests(&samples, 8, 200, 7));
+        assert!(report.is_iid(), "{:?}", report.tests.iter().filter(|test| !test.passed()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,57 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+pub mod assessment;
+pub mod chi_square;
+pub mod estimators;
+pub mod iid;
+pub mod predictors;
+pub mod restart;
+pub mod suffix_array;
+
+/// Splits bytes into symbols of `bits_per_symbol` bits (1, 2, 4 or 8), most significant first.
+pub fn symbols(bytes: &[u8], bits_per_symbol: usize) -> Vec<u8> {
+    assert!([1, 2, 4, 8].contains(&bits_per_symbol), "Symbols must have 1, 2, 4 or 8 bits.");
+    let mask = ((1u16 << bits_per_symbol) - 1) as u8;
+    bytes
+        .iter()
+        .flat_map(|&byte| (0..8 / bits_per_symbol).rev().map(move |i| (byte >> (i * bits_per_symbol)) & mask))
+        .collect()
+}
+
+/// Conversion I of SP 800-90B: the number of ones in each complete block of 8 binary samples.
+pub(crate) fn hamming_weights(bits: &[u8]) -> Vec<u8> {
+    bits.chunks_exact(8).map(|block| block.iter().sum()).collect()
+}
+
+/// Conversion II of SP 800-90B: each complete block of 8 binary samples read as a byte.
+pub(crate) fn pack_bits(bits: &[u8]) -> Vec<u8> {
+    bits.chunks_exact(8).map(|block| block.iter().fold(0, |byte, &bit| (byte << 1) | bit)).collect()
+}
+
+/// The bits of `symbols` of `bits_per_symbol` bits each, most significant first, one bit per byte.
+pub(crate) fn bitstring(symbols: &[u8], bits_per_symbol: usize) -> Vec<u8> {
+    symbols.iter().flat_map(|&symbol| (0..bits_per_symbol).rev().map(move |i| (symbol >> i) & 1)).collect()
+}
@@ -0,0 +1,372 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::collections::HashMap;
+
+use crate::entropy::estimators::{Estimate, Z_99, solve_decreasing};
+
+/// Window sizes of the MultiMCW subpredictors.
+pub const MCW_WINDOWS: [usize; 4] = [63, 255, 1023, 4095];
+
+/// Number of lag subpredictors.
+pub const LAG_DEPTH: usize = 128;
+
+/// Longest context of the MultiMMC subpredictors.
+pub const MMC_DEPTH: usize = 16;
+
+/// Most (context, symbol) pairs each MultiMMC subpredictor keeps.
+pub const MMC_MAX_ENTRIES: usize = 100_000;
+
+/// Longest context of the LZ78Y predictor.
+pub const LZ78Y_DEPTH: usize = 16;
+
+/// Most contexts the LZ78Y dictionary keeps.
+pub const LZ78Y_MAX_DICTIONARY: usize = 65_536;
+
+/// Outcome of a predictor estimate (SP 800-90B, 6.3.7 to 6.3.10).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PredictorEstimate {
+    pub name: &'static str,
+    pub predictions: usize,
+    pub correct: usize,
+    /// Longest run of correct predictions.
+    pub longest_run: usize,
+    /// Upper 99% bound on the rate of correct predictions.
+    pub p_global: f64,
+    /// Probability of a correct prediction for which the longest run would be exceeded with only 1% chance.
+    pub p_local: f64,
+    /// Min-entropy per sample implied by the larger bound, at most the symbol width.
+    pub entropy: f64,
+}
+
+impl PredictorEstimate {
+    fn new(name: &'static str, correct: &[bool], bits_per_symbol: usize) -> Self {
+        let n = correct.len();
+        let hits = correct.iter().filter(|&&hit| hit).count();
+        let longest_run = correct.split(|&hit| !hit).map(<[bool]>::len).max().unwrap_or(0);
+
+        let p = hits as f64 / n as f64;
+        let p_global = if hits == 0 {
+            1.0 - 0.01f64.powf(1.0 / n as f64)
+        } else {
+            f64::min(1.0, p + Z_99 * (p * (1.0 - p) / (n as f64 - 1.0)).sqrt())
+        };
+        let p_local = local_bound(n, longest_run + 1);
+        let guess = 0.5f64.powi(bits_per_symbol as i32);
+        let entropy = -p_global.max(p_local).max(guess).log2();
+        PredictorEstimate { name, predictions: n, correct: hits, longest_run, p_global, p_local, entropy }
+    }
+
+    pub fn estimate(&self) -> Estimate {
+        Estimate { name: self.name, entropy: self.entropy }
+    }
+}
+
+/// Solves 0.99 = (1 - px) / ((r + 1 - rx) q) / x^(n + 1) for p, where q = 1 - p and x approximates the root of
+/// 1 - x + q p^r x^(r + 1) after 10 iterations, as SP 800-90B specifies.
+fn local_bound(n: usize, r: usize) -> f64 {
+    let no_run = |p: f64| {
+        let q = 1.0 - p;
+        let mut x = 1.0f64;
+        for _ in 0..10 {
+            x = 1.0 + q * p.powi(r as i32) * x.powi(r as i32 + 1);
+        }
+        // In logarithms, as x^(n + 1) overflows for long inputs
+        ((1.0 - p * x).ln() - ((r as f64 + 1.0 - r as f64 * x) * q).ln() - (n as f64 + 1.0) * x.ln()).exp()
+    };
+    solve_decreasing(no_run, 0.99, 0.0, 1.0 - f64::EPSILON).unwrap_or(0.0)
+}
+
+/// Index of the subpredictor that has been right most often, preferring later ones on ties.
+fn update_scoreboard(scoreboard: &mut [usize], winner: &mut usize, predictions: &[Option<u8>], sample: u8) {
+    for (j, &prediction) in predictions.iter().enumerate() {
+        if prediction == Some(sample) {
+            scoreboard[j] += 1;
+            if scoreboard[j] >= scoreboard[*winner] {
+                *winner = j;
+            }
+        }
+    }
+}
+
+/// Counts of the symbols that followed one context, with the most frequent at hand, the largest on ties.
+#[derive(Clone, Default)]
+struct Followers {
+    counts: Vec<(u8, u32)>,
+    best: Option<(u8, u32)>,
+}
+
+impl Followers {
+    /// Counts `symbol`, adding it only if it was seen before or `add` allows a new entry. Returns whether it was added.
+    fn count(&mut self, symbol: u8, add: bool) -> bool {
+        let (count, added) = match self.counts.iter_mut().find(|(seen, _)| *seen == symbol) {
+            Some((_, count)) => {
+                *count += 1;
+                (*count, false)
+            }
+            None if add => {
+                self.counts.push((symbol, 1));
+                (1, true)
+            }
+            None => return false,
+        };
+        if self.best.is_none_or(|(best, most)| (count, symbol) > (most, best)) {
+            self.best = Some((symbol, count));
+        }
+        added
+    }
+}
+
+/// Multi most common in window prediction estimate (SP 800-90B, 6.3.7).
+///
+/// Subpredictors guess the commonest symbol of the last 63, 255, 1023 or 4095 samples, the most recent on ties.
+pub fn multi_mcw(symbols: &[u8], bits_per_symbol: usize) -> PredictorEstimate {
+    let alphabet = 1 << bits_per_symbol;
+    let mut counts = [[0u32; 256]; MCW_WINDOWS.len()];
+    let mut last_seen = [0usize; 256];
+    let mut frequent = [None; MCW_WINDOWS.len()];
+    let mut scoreboard = [0; MCW_WINDOWS.len()];
+    let mut winner = 0;
+    let mut correct = Vec::with_capacity(symbols.len().saturating_sub(MCW_WINDOWS[0]));
+
+    for (i, &sample) in symbols.iter().enumerate() {
+        if i >= MCW_WINDOWS[0] {
+            let mut predictions = frequent;
+            for (prediction, &window) in predictions.iter_mut().zip(&MCW_WINDOWS) {
+                if i < window {
+                    *prediction = None;
+                }
+            }
+            correct.push(predictions[winner] == Some(sample));
+            update_scoreboard(&mut scoreboard, &mut winner, &predictions, sample);
+        }
+        last_seen[sample as usize] = i;
+        for (j, &window) in MCW_WINDOWS.iter().enumerate() {
+            // The newest sample becomes the mode when it ties, and only dropping the mode calls for a rescan
+            let counts = &mut counts[j];
+            counts[sample as usize] += 1;
+            if frequent[j].is_none_or(|mode: u8| counts[sample as usize] >= counts[mode as usize]) {
+                frequent[j] = Some(sample);
+            }
+            if i >= window {
+                let oldest = symbols[i - window];
+                counts[oldest as usize] -= 1;
+                if frequent[j] == Some(oldest) {
+                    frequent[j] = (0..alphabet)
+                        .filter(|&symbol| counts[symbol] > 0)
+                        .max_by_key(|&symbol| (counts[symbol], last_seen[symbol]))
+                        .map(|symbol| symbol as u8);
+                }
+            }
+        }
+    }
+    PredictorEstimate::new("multi_mcw", &correct, bits_per_symbol)
+}
+
+/// Lag prediction estimate (SP 800-90B, 6.3.8): subpredictors guess the sample 1 to 128 positions back.
+pub fn lag(symbols: &[u8], bits_per_symbol: usize) -> PredictorEstimate {
+    let mut scoreboard = [0; LAG_DEPTH];
+    let mut winner = 0;
+    let mut lags = [None; LAG_DEPTH];
+    let mut correct = Vec::with_capacity(symbols.len().saturating_sub(1));
+
+    for (i, &sample) in symbols.iter().enumerate().skip(1) {
+        for (d, lag) in lags.iter_mut().enumerate().take(i) {
+            *lag = Some(symbols[i - d - 1]);
+        }
+        correct.push(lags[winner] == Some(sample));
+        update_scoreboard(&mut scoreboard, &mut winner, &lags, sample);
+    }
+    PredictorEstimate::new("lag", &correct, bits_per_symbol)
+}
+
+/// Contexts of the last 1 to `depth` samples before `end`, packed 8 bits per symbol, shortest first.
+fn contexts(symbols: &[u8], end: usize, depth: usize) -> impl Iterator<Item = u128> + '_ {
+    symbols[end.saturating_sub(depth)..end]
+        .iter()
+        .rev()
+        .scan(0u128, |context, &symbol| {
+            *context = (*context << 8) | symbol as u128;
+            Some(*context)
+        })
+}
+
+/// Multi Markov model with counting prediction estimate (SP 800-90B, 6.3.9).
+///
+/// Subpredictors of order 1 to 16 guess the symbol that most often followed the last d samples.
+pub fn multi_mmc(symbols: &[u8], bits_per_symbol: usize) -> PredictorEstimate {
+    let mut models: Vec<HashMap<u128, Followers>> = vec![HashMap::new(); MMC_DEPTH];
+    let mut entries = [0; MMC_DEPTH];
+    let mut scoreboard = [0; MMC_DEPTH];
+    let mut winner = 0;
+    let mut correct = Vec::with_capacity(symbols.len().saturating_sub(2));
+
+    for (i, &sample) in symbols.iter().enumerate().skip(2) {
+        // Count the transitions into the previous sample
+        let next = symbols[i - 1];
+        for (d, context) in contexts(symbols, i - 1, MMC_DEPTH).enumerate() {
+            let room = entries[d] < MMC_MAX_ENTRIES;
+            let followers = match models[d].get_mut(&context) {
+                Some(followers) => followers,
+                None if room => models[d].entry(context).or_default(),
+                None => continue,
+            };
+            if followers.count(next, room) {
+                entries[d] += 1;
+            }
+        }
+
+        let mut predictions = [None; MMC_DEPTH];
+        for (d, context) in contexts(symbols, i, MMC_DEPTH).enumerate() {
+            predictions[d] = models[d].get(&context).and_then(|followers| followers.best).map(|(y, _)| y);
+        }
+        correct.push(predictions[winner] == Some(sample));
+        update_scoreboard(&mut scoreboard, &mut winner, &predictions, sample);
+    }
+    PredictorEstimate::new("multi_mmc", &correct, bits_per_symbol)
+}
+
+/// LZ78Y prediction estimate (SP 800-90B, 6.3.10).
+///
+/// Guesses the symbol that most often followed the longest context of up to 16 samples in a dictionary that
+/// stops growing at 65,536 contexts.
+pub fn lz78y(symbols: &[u8], bits_per_symbol: usize) -> PredictorEstimate {
+    let mut dictionary: Vec<HashMap<u128, Followers>> = vec![HashMap::new(); LZ78Y_DEPTH];
+    let mut size = 0;
+    let mut correct = Vec::with_capacity(symbols.len().saturating_sub(LZ78Y_DEPTH + 1));
+
+    for (i, &sample) in symbols.iter().enumerate().skip(LZ78Y_DEPTH + 1) {
+        let next = symbols[i - 1];
+        let mut previous = [0u128; LZ78Y_DEPTH];
+        contexts(symbols, i - 1, LZ78Y_DEPTH).zip(previous.iter_mut()).for_each(|(context, slot)| *slot = context);
+        for (j, &context) in previous.iter().enumerate().rev() {
+            if !dictionary[j].contains_key(&context) && size < LZ78Y_MAX_DICTIONARY {
+                dictionary[j].insert(context, Followers::default());
+                size += 1;
+            }
+            if let Some(followers) = dictionary[j].get_mut(&context) {
+                followers.count(next, true);
+            }
+        }
+
+        // Longer contexts win ties between contexts
+        let mut current = [0u128; LZ78Y_DEPTH];
+        contexts(symbols, i, LZ78Y_DEPTH).zip(current.iter_mut()).for_each(|(context, slot)| *slot = context);
+        let mut prediction = None;
+        let mut max_count = 0;
+        for (j, context) in current.iter().enumerate().rev() {
+            if let Some((y, count)) = dictionary[j].get(context).and_then(|followers| followers.best) {
+                if count > max_count {
+                    prediction = Some(y);
+                    max_count = count;
+                }
+            }
+        }
+        correct.push(prediction == Some(sample));
+    }
+    PredictorEstimate::new("lz78y", &correct, bits_per_symbol)
+}
+
+/// All four predictor estimates of `symbols`.
+pub fn predictors(symbols: &[u8], bits_per_symbol: usize) -> [PredictorEstimate; 4] {
+    [
+        multi_mcw(symbols, bits_per_symbol),
+        lag(symbols, bits_per_symbol),
+        multi_mmc(symbols, bits_per_symbol),
+        lz78y(symbols, bits_per_symbol),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entropy::bitstring;
+    use crate::stream::testing::E;
+
+    fn assert_estimate(estimate: &PredictorEstimate, expected: (usize, usize, usize, f64, f64, f64)) {
+        let (predictions, correct, longest_run, p_global, p_local, entropy) = expected;
+        assert_eq!((estimate.predictions, estimate.correct, estimate.longest_run), (predictions, correct, longest_run));
+        assert!((estimate.p_global - p_global).abs() < 1e-9, "{}: p_global = {}", estimate.name, estimate.p_global);
+        assert!((estimate.p_local - p_local).abs() < 1e-9, "{}: p_local = {}", estimate.name, estimate.p_local);
+        assert!((estimate.entropy - entropy).abs() < 1e-9, "{}: entropy = {}", estimate.name, estimate.entropy);
+    }
+
+    #[test]
+    fn estimates_match_reference() {
+        // Computed by a straightforward transcription of SP 800-90B 6.3.7 to 6.3.10, which recounts every window
+        // and keeps contexts as tuples, with P_local solved in 40-digit arithmetic
+        let bits = bitstring(&E[..2_500], 8);
+        let expected = [
+            (19937, 9953, 11, 0.5083446824731077, 0.30800204496281, 0.976121048540918),
+            (19999, 10037, 14, 0.510983020460104, 0.39315021469577327, 0.968652742481796),
+            (19998, 10022, 14, 0.5102583093991002, 0.3931515853570072, 0.970700323594553),
+            (19983, 9996, 14, 0.509336826989654, 0.3931721541263027, 0.9733080615424642),
+        ];
+        for (estimate, expected) in predictors(&bits, 1).iter().zip(expected) {
+            assert_estimate(estimate, expected);
+        }
+
+        // Nibbles of e capped at 3, as 2-bit symbols and as bits
+        let symbols: Vec<u8> = E[..5_000].iter().flat_map(|&byte| [(byte >> 4).min(3), (byte & 15).min(3)]).collect();
+        let expected = [
+            (9937, 8065, 38, 0.8217182459378276, 0.7256403532568463, 0.2832842932783629),
+            (9999, 6646, 33, 0.6768291575317877, 0.6895659993683506, 0.5362394540914008),
+            (9998, 8113, 38, 0.8215395949605394, 0.7255177877105378, 0.28359798583906076),
+            (9983, 8099, 38, 0.8213678036426002, 0.7255478546406426, 0.28389969789364483),
+        ];
+        for (estimate, expected) in predictors(&symbols, 2).iter().zip(expected) {
+            assert_estimate(estimate, expected);
+        }
+        let expected = [
+            (19937, 17438, 76, 0.8806960200270676, 0.8489772811043229, 0.18328394869914855),
+            (19999, 16527, 75, 0.8332910348193943, 0.8469677861891821, 0.2396209961550595),
+            (19998, 17491, 76, 0.8806694640984805, 0.8489408203027411, 0.18332745143248477),
+            (19983, 17476, 76, 0.8805795654293712, 0.8489497754953409, 0.1834747291451133),
+        ];
+        for (estimate, expected) in predictors(&bitstring(&symbols, 2), 1).iter().zip(expected) {
+            assert_estimate(estimate, expected);
+        }
+    }
+
+    #[test]
+    fn bounds_without_correct_predictions() {
+        // With no correct prediction P_global is the rate at which none would be seen with 1% chance, and the
+        // guess of 1/4 bounds the entropy of 2-bit symbols
+        let estimate = PredictorEstimate::new("lag", &[false; 100], 2);
+        assert!((estimate.p_global - (1.0 - 0.01f64.powf(0.01))).abs() < 1e-15);
+        assert_eq!(estimate.longest_run, 0);
+        assert!((estimate.p_local - 0.00010049830824166791).abs() < 1e-12, "p_local = {}", estimate.p_local);
+        assert_eq!(estimate.entropy, 2.0);
+    }
+
+    #[test]
+    fn lag_learns_a_period() {
+        // Lag 1, the initial winner, is right on the third sample; lag 3 ties it with its first hit on the fourth,
+        // takes over and is right from there on
+        let symbols: Vec<u8> = [0, 1, 1].repeat(100);
+        let estimate = lag(&symbols, 1);
+        assert_eq!((estimate.predictions, estimate.correct, estimate.longest_run), (299, 297, 296));
+    }
+}
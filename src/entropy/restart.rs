@@ -0,0 +1,275 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::path::Path;
+
+use crate::Onod;
+use crate::entropy::estimators::{MinEntropyReport, assess, assess_iid};
+use crate::entropy::symbols;
+use crate::health::critical_binomial;
+
+/// Number of restarts and samples per restart that SP 800-90B section 3.1.4 asks for.
+pub const RESTARTS: usize = 1000;
+pub const RESTART_SAMPLES: usize = 1000;
+
+/// Samples collected over restarts of the noise source, one row per restart.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestartMatrix {
+    pub bits_per_symbol: usize,
+    pub rows: usize,
+    pub columns: usize,
+    /// Symbols in row-major order.
+    samples: Vec<u8>,
+}
+
+impl RestartMatrix {
+    /// Arranges `samples`, symbols of `bits_per_symbol` bits, in rows of `columns` samples.
+    pub fn new(samples: Vec<u8>, bits_per_symbol: usize, columns: usize) -> Result<Self, String> {
+        if columns == 0 || samples.is_empty() || !samples.len().is_multiple_of(columns) {
+            return Err(format!("{} samples do not make up rows of {} samples", samples.len(), columns));
+        }
+        Ok(RestartMatrix { bits_per_symbol, rows: samples.len() / columns, columns, samples })
+    }
+
+    /// Reads a matrix from `path`: a file with the rows one after another, or a directory with one capture per
+    /// restart, in file name order, of which the first `columns` samples are used.
+    pub fn load<P: AsRef<Path>>(path: P, bits_per_symbol: usize, columns: usize) -> Result<Self, String> {
+        let path = path.as_ref();
+        let read = |path: &Path| std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e));
+        if !path.is_dir() {
+            let samples = symbols(&read(path)?, bits_per_symbol);
+            return RestartMatrix::new(samples, bits_per_symbol, columns).map_err(|e| format!("{}: {}", path.display(), e));
+        }
+
+        let mut captures = std::fs::read_dir(path)
+            .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        captures.retain(|capture| capture.is_file());
+        captures.sort();
+
+        let mut samples = Vec::with_capacity(captures.len() * columns);
+        for capture in &captures {
+            let restart = symbols(&read(capture)?, bits_per_symbol);
+            if restart.len() < columns {
+                return Err(format!("{}: {} samples, {} needed", capture.display(), restart.len(), columns));
+            }
+            samples.extend_from_slice(&restart[..columns]);
+        }
+        RestartMatrix::new(samples, bits_per_symbol, columns).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Whether the matrix has the 1000 restarts of 1000 samples the standard requires.
+    pub fn is_full_size(&self) -> bool {
+        self.rows >= RESTARTS && self.columns >= RESTART_SAMPLES
+    }
+
+    /// The samples of every restart, one after another.
+    pub fn row_dataset(&self) -> &[u8] {
+        &self.samples
+    }
+
+    /// The i-th samples of all restarts, for each i in turn.
+    pub fn column_dataset(&self) -> Vec<u8> {
+        (0..self.columns).flat_map(|column| self.samples[column..].iter().step_by(self.columns).copied()).collect()
+    }
+}
+
+/// The sanity check of SP 800-90B section 3.1.4.3: no value may be too common in any restart or column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SanityCheck {
+    /// Highest count of the most common value within one row, and the count at which it fails the check.
+    pub row_max: usize,
+    pub row_cutoff: usize,
+    /// The same for the columns.
+    pub column_max: usize,
+    pub column_cutoff: usize,
+}
+
+impl SanityCheck {
+    pub fn passed(&self) -> bool {
+        self.row_max < self.row_cutoff && self.column_max < self.column_cutoff
+    }
+}
+
+/// Restart test results: the sanity check and the estimates on the row and column datasets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestartReport {
+    /// Min-entropy per sample claimed from the sequential data, H_I.
+    pub h_initial: f64,
+    pub sanity: SanityCheck,
+    pub rows: MinEntropyReport,
+    pub columns: MinEntropyReport,
+}
+
+impl RestartReport {
+    /// Min-entropy per sample of the row dataset, H_r.
+    pub fn h_r(&self) -> f64 {
+        self.rows.min_entropy()
+    }
+
+    /// Min-entropy per sample of the column dataset, H_c.
+    pub fn h_c(&self) -> f64 {
+        self.columns.min_entropy()
+    }
+
+    /// Whether neither H_r nor H_c falls below half of H_I (section 3.1.4.2).
+    pub fn validated(&self) -> bool {
+        self.h_r().min(self.h_c()) >= self.h_initial / 2.0
+    }
+
+    pub fn passed(&self) -> bool {
+        self.sanity.passed() && self.validated()
+    }
+
+    /// Min-entropy per sample after the restart tests: H_I lowered to H_r or H_c where they are smaller.
+    pub fn min_entropy(&self) -> f64 {
+        self.h_initial.min(self.h_r()).min(self.h_c())
+    }
+}
+
+/// The sanity check for a claim of `h_initial` bits per sample: fails when the most common value of a row or
+/// column of n samples occurs more often than a binomial(n, 2^-H_I) count exceeds with probability
+/// 0.01 / (k × 2000), k being the number of possible sample values.
+pub fn sanity_check(matrix: &RestartMatrix, h_initial: f64) -> SanityCheck {
+    let most_common = |values: &mut dyn Iterator<Item = u8>| {
+        let mut counts = [0usize; 256];
+        values.for_each(|value| counts[value as usize] += 1);
+        counts.into_iter().max().unwrap_or(0)
+    };
+    let row_max = matrix.samples.chunks_exact(matrix.columns).map(|row| most_common(&mut row.iter().copied())).max();
+    let column_max = (0..matrix.columns)
+        .map(|column| most_common(&mut matrix.samples[column..].iter().step_by(matrix.columns).copied()))
+        .max();
+
+    let alpha = 0.01 / ((1u64 << matrix.bits_per_symbol) as f64 * 2000.0);
+    let cutoff = |length: usize| 1 + critical_binomial(length as u32, (-h_initial).exp2(), alpha) as usize;
+    SanityCheck {
+        row_max: row_max.unwrap_or(0),
+        row_cutoff: cutoff(matrix.columns),
+        column_max: column_max.unwrap_or(0),
+        column_cutoff: cutoff(matrix.rows),
+    }
+}
+
+/// Runs the restart tests of SP 800-90B section 3.1.4 on `matrix` against the claim `h_initial`. The row and
+/// column datasets are assessed like the sequential data: with the most common value estimate alone when it
+/// passed the IID tests (`iid`), otherwise with all estimators.
+pub fn restart_tests(matrix: &RestartMatrix, h_initial: f64, iid: bool) -> RestartReport {
+    let assess = if iid { assess_iid } else { assess };
+    RestartReport {
+        h_initial,
+        sanity: sanity_check(matrix, h_initial),
+        rows: assess(matrix.row_dataset(), matrix.bits_per_symbol),
+        columns: assess(&matrix.column_dataset(), matrix.bits_per_symbol),
+    }
+}
+
+impl Onod {
+
+    /// Restart testing (NIST SP 800-90B, 3.1.4) of the samples of a series of restarts, 1000 of 1000 samples
+    /// each for validation, against the min-entropy `h_initial` estimated from the sequential data.
+    pub fn restart_tests(matrix: &RestartMatrix, h_initial: f64, iid: bool) -> RestartReport {
+        restart_tests(matrix, h_initial, iid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// A fresh directory under the system's temporary directory.
+    fn scratch(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("onod3000-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn rejects_ragged_input() {
+        assert!(RestartMatrix::new(vec![0; 10], 8, 3).is_err());
+        assert!(RestartMatrix::new(vec![0; 10], 8, 0).is_err());
+        assert!(RestartMatrix::new(Vec::new(), 8, 3).is_err());
+
+        let dir = scratch("ragged");
+        let file = dir.join("restarts.bin");
+        fs::write(&file, [0u8; 10]).unwrap();
+        let error = RestartMatrix::load(&file, 8, 3).unwrap_err();
+        assert!(error.contains("restarts.bin") && error.contains("10 samples"), "{}", error);
+        // 80 bits make up 16 rows of 5
+        assert_eq!(RestartMatrix::load(&file, 1, 5).unwrap().rows, 16);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn loads_captures_in_name_order() {
+        let dir = scratch("captures");
+        fs::write(dir.join("b"), [4, 5, 6, 0xff]).unwrap();
+        fs::write(dir.join("a"), [1, 2, 3]).unwrap();
+        fs::write(dir.join("c"), [7, 8, 9]).unwrap();
+        let matrix = RestartMatrix::load(&dir, 8, 3).unwrap();
+        assert_eq!((matrix.rows, matrix.columns), (3, 3));
+        assert_eq!(matrix.row_dataset(), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(matrix.column_dataset(), [1, 4, 7, 2, 5, 8, 3, 6, 9]);
+
+        fs::write(dir.join("d"), [10, 11]).unwrap();
+        let error = RestartMatrix::load(&dir, 8, 3).unwrap_err();
+        assert!(error.ends_with("d: 2 samples, 3 needed"), "{}", error);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn column_dataset_reads_down_the_columns() {
+        let matrix = RestartMatrix::new(vec![1, 2, 3, 4, 5, 6], 8, 3).unwrap();
+        assert_eq!(matrix.rows, 2);
+        assert_eq!(matrix.column_dataset(), [1, 4, 2, 5, 3, 6]);
+    }
+
+    #[test]
+    fn sanity_check_cutoffs() {
+        // Smallest u with P(X >= u) <= 0.01 / (2 * 2000) for X ~ binomial(200, 2^-0.8) and binomial(100, 2^-0.8),
+        // summed in 50-digit arithmetic
+        let alternating: Vec<u8> =
+            (0..100).flat_map(|row| (0..200).map(move |column| ((row + column) % 2) as u8)).collect();
+        let matrix = RestartMatrix::new(alternating.clone(), 1, 200).unwrap();
+        let check = sanity_check(&matrix, 0.8);
+        assert_eq!(check, SanityCheck { row_max: 100, row_cutoff: 147, column_max: 50, column_cutoff: 80 });
+        assert!(check.passed());
+
+        // A stuck restart fails
+        let mut stuck = alternating;
+        stuck[200..400].fill(1);
+        let check = sanity_check(&RestartMatrix::new(stuck, 1, 200).unwrap(), 0.8);
+        assert_eq!((check.row_max, check.column_max), (200, 51));
+        assert!(!check.passed());
+
+        // 1000 restarts of 1000 bytes claimed at 7 bits: binomial(1000, 2^-7) with alpha = 0.01 / (256 * 2000)
+        let check = sanity_check(&RestartMatrix::new(vec![0; 1_000_000], 8, 1000).unwrap(), 7.0);
+        assert_eq!((check.row_cutoff, check.column_cutoff), (28, 28));
+    }
+}
@@ -0,0 +1,227 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+/// Largest key range of the initial counting sort.
+const MAX_BUCKETS: usize = 1 << 20;
+
+/// Suffix array of `data`: the starting positions of its suffixes in lexicographic order.
+///
+/// Prefix doubling with counting sorts. The first sort already orders the suffixes by as many leading symbols
+/// as fit into 2^20 keys, 12 for bits and 2 for bytes, which saves most of the doubling rounds.
+pub fn suffix_array(data: &[u8]) -> Vec<u32> {
+    let n = data.len();
+    assert!(n < u32::MAX as usize, "Suffix arrays are limited to 4 GB.");
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // Keys of the first h symbols in base (alphabet + 1), with 0 past the end so shorter suffixes sort first
+    let base = *data.iter().max().unwrap_or(&0) as usize + 2;
+    let mut h = 1;
+    while h < n && base.pow(h as u32 + 1) <= MAX_BUCKETS {
+        h += 1;
+    }
+    let keys: Vec<u32> = (0..n)
+        .map(|i| (i..i + h).fold(0, |key, j| key * base + data.get(j).map_or(0, |&symbol| symbol as usize + 1)) as u32)
+        .collect();
+    let mut sa = vec![0u32; n];
+    let mut counts = vec![0u32; base.pow(h as u32) + 1];
+    counting_sort(0..n as u32, &keys, &mut counts, &mut sa);
+
+    // Dense ranks so that later counting sorts need at most n buckets
+    let mut rank = vec![0u32; n];
+    for j in 1..n {
+        let (previous, current) = (sa[j - 1] as usize, sa[j] as usize);
+        rank[current] = rank[previous] + (keys[current] != keys[previous]) as u32;
+    }
+    drop(keys);
+
+    let mut order = vec![0u32; n];
+    counts.resize(n + 1, 0);
+    let mut k = h;
+    while (rank[sa[n - 1] as usize] as usize) < n - 1 {
+        // Sorted by the rank k positions on, suffixes too short for one first
+        let second = (n.saturating_sub(k)..n).map(|i| i as u32).chain(sa.iter().filter(|&&i| i as usize >= k).map(|&i| i - k as u32));
+        order.clear();
+        order.extend(second);
+        counts.iter_mut().for_each(|count| *count = 0);
+        counting_sort(order.iter().copied(), &rank, &mut counts, &mut sa);
+
+        let key = |i: usize| (rank[i], rank.get(i + k).map_or(-1, |&r| r as i64));
+        order[sa[0] as usize] = 0;
+        for j in 1..n {
+            let (previous, current) = (sa[j - 1] as usize, sa[j] as usize);
+            order[current] = order[previous] + (key(current) != key(previous)) as u32;
+        }
+        std::mem::swap(&mut rank, &mut order);
+        k *= 2;
+    }
+    sa
+}
+
+/// Stable counting sort of `items` by `keys[item]` into `sorted`.
+fn counting_sort(items: impl Iterator<Item = u32> + Clone, keys: &[u32], counts: &mut [u32], sorted: &mut [u32]) {
+    for item in items.clone() {
+        counts[keys[item as usize] as usize + 1] += 1;
+    }
+    for key in 1..counts.len() {
+        counts[key] += counts[key - 1];
+    }
+    for item in items {
+        let slot = &mut counts[keys[item as usize] as usize];
+        sorted[*slot as usize] = item;
+        *slot += 1;
+    }
+}
+
+/// Longest common prefix of every suffix in `sa` with the one before it (Kasai et al.), 0 for the first.
+pub fn lcp_array(data: &[u8], sa: &[u32]) -> Vec<u32> {
+    let n = data.len();
+    let mut rank = vec![0u32; n];
+    for (j, &i) in sa.iter().enumerate() {
+        rank[i as usize] = j as u32;
+    }
+    let mut lcp = vec![0u32; n];
+    let mut length = 0;
+    for i in 0..n {
+        let j = rank[i] as usize;
+        if j == 0 {
+            length = 0;
+            continue;
+        }
+        let previous = sa[j - 1] as usize;
+        while i + length < n && previous + length < n && data[i + length] == data[previous + length] {
+            length += 1;
+        }
+        lcp[j] = length as u32;
+        length = length.saturating_sub(1);
+    }
+    lcp
+}
+
+/// How often tuples of every length repeat: for each length W from 1 on, the count of the most common W-tuple and
+/// the number of pairs of positions at which the same W-tuple starts. Lengths beyond the longest repeated
+/// substring are left out.
+pub fn tuple_repeats(data: &[u8]) -> (Vec<u64>, Vec<u64>) {
+    let sa = suffix_array(data);
+    let lcp = lcp_array(data, &sa);
+    drop(sa);
+    let longest = lcp.iter().copied().max().unwrap_or(0) as usize;
+    let mut most = vec![1u64; longest + 1];
+    let mut pairs = vec![0i64; longest + 2];
+
+    // Each lcp-interval of value l with an enclosing interval of value p groups its suffixes by W-tuple for p < W <= l
+    let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+    for i in 1..=lcp.len() {
+        let current = lcp.get(i).map_or(0, |&l| l as usize);
+        let mut left = i - 1;
+        while current < stack.last().map_or(0, |&(l, _)| l) {
+            let (l, start) = stack.pop().unwrap_or((0, 0));
+            let size = (i - start) as u64;
+            let parent = current.max(stack.last().map_or(0, |&(l, _)| l));
+            most[l] = most[l].max(size);
+            pairs[parent + 1] += (size * (size - 1) / 2) as i64;
+            pairs[l + 1] -= (size * (size - 1) / 2) as i64;
+            left = start;
+        }
+        if current > stack.last().map_or(0, |&(l, _)| l) {
+            stack.push((current, left));
+        }
+    }
+
+    // A group of W-tuples holds the groups of all longer tuples, so counts only grow towards shorter lengths
+    for w in (1..longest).rev() {
+        most[w] = most[w].max(most[w + 1]);
+    }
+    let mut running = 0;
+    let pairs = pairs[..=longest]
+        .iter()
+        .map(|&delta| {
+            running += delta;
+            running as u64
+        })
+        .collect();
+    (most, pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::stream::testing::xorshift_bytes;
+
+    /// Random inputs over alphabets of 2, 4, 16 and 256 symbols and periodic ones, long enough to need several
+    /// doubling rounds after the initial sort.
+    fn inputs() -> Vec<Vec<u8>> {
+        let mut inputs = vec![vec![], vec![7], vec![0; 50], b"banana".to_vec(), b"mississippi".to_vec()];
+        for (k, len) in [2, 3, 13, 100, 257, 1000].into_iter().enumerate() {
+            let random = xorshift_bytes(len + k);
+            for mask in [1, 3, 15, 255] {
+                inputs.push(random.iter().map(|&byte| byte & mask).collect());
+            }
+            inputs.push(random.iter().take(5).copied().cycle().take(len).collect());
+        }
+        inputs
+    }
+
+    #[test]
+    fn suffix_array_matches_naive() {
+        for data in inputs() {
+            let mut naive: Vec<u32> = (0..data.len() as u32).collect();
+            naive.sort_by_key(|&i| &data[i as usize..]);
+            let sa = suffix_array(&data);
+            assert_eq!(sa, naive, "{:?}", data);
+
+            let common = |i: u32, j: u32| {
+                data[i as usize..].iter().zip(&data[j as usize..]).take_while(|(a, b)| a == b).count() as u32
+            };
+            let naive_lcp: Vec<u32> =
+                (0..sa.len()).map(|j| if j == 0 { 0 } else { common(sa[j - 1], sa[j]) }).collect();
+            assert_eq!(lcp_array(&data, &sa), naive_lcp, "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn tuple_repeats_match_naive() {
+        for data in inputs() {
+            let (most, pairs) = tuple_repeats(&data);
+            let mut naive = (vec![1], vec![0]);
+            for w in 1..data.len() {
+                let mut counts = HashMap::new();
+                for tuple in data.windows(w) {
+                    *counts.entry(tuple).or_insert(0u64) += 1;
+                }
+                let max = counts.values().copied().max().unwrap_or(0);
+                if max < 2 {
+                    break;
+                }
+                naive.0.push(max);
+                naive.1.push(counts.values().map(|&count| count * (count - 1) / 2).sum());
+            }
+            assert_eq!((most, pairs), naive, "{:?}", data);
+        }
+    }
+}
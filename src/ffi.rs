@@ -30,8 +30,11 @@ use std::ffi::{c_char, c_double, CStr};
 
 use crate::Onod;
 
+/// # Safety
+/// `test` must be a valid NUL-terminated string, `samples` must point to `len` readable bytes
+/// and `result` must point to space for three doubles.
 #[no_mangle]
-pub extern "C" fn onod_run(
+pub unsafe extern "C" fn onod_run(
     test: *const c_char,
     samples: *const u8,
     len: usize,
@@ -55,7 +58,7 @@ pub extern "C" fn onod_run(
     };
 
     // Convert samples to a Rust slice
-    let samples = unsafe { std::slice::from_raw_parts(samples, len as usize) };
+    let samples = unsafe { std::slice::from_raw_parts(samples, len) };
 
     // Call the Rust `run` function
     let (obs, z, p) = Onod::run(test, samples);
@@ -68,4 +71,4 @@ pub extern "C" fn onod_run(
     }
 
     true
-}
\ No newline at end of file
+}
@@ -0,0 +1,201 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// Complex number in cartesian form.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    /// exp(i * angle)
+    pub fn from_angle(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Complex::new(cos, sin)
+    }
+
+    pub fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    pub fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+}
+
+/// Discrete Fourier transform, X_k = sum_j x_j * exp(-2 pi i j k / n), of any length.
+///
+/// Powers of two use an in-place radix-2 FFT, other lengths Bluestein's algorithm on top of it.
+pub fn fft(data: &mut [Complex]) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+    if n.is_power_of_two() {
+        radix2(data, false);
+    } else {
+        bluestein(data);
+    }
+}
+
+/// Iterative Cooley-Tukey FFT, or its unscaled inverse. `data.len()` must be a power of two.
+fn radix2(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+
+    // Bit reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+    while length <= n {
+        // Twiddle factors computed directly rather than by repeated multiplication to limit rounding
+        let half = length / 2;
+        let twiddles: Vec<Complex> = (0..half)
+            .map(|k| Complex::from_angle(sign * 2.0 * PI * k as f64 / length as f64))
+            .collect();
+        for block in data.chunks_exact_mut(length) {
+            let (low, high) = block.split_at_mut(half);
+            for ((a, b), &w) in low.iter_mut().zip(high.iter_mut()).zip(twiddles.iter()) {
+                let t = *b * w;
+                *b = *a - t;
+                *a = *a + t;
+            }
+        }
+        length <<= 1;
+    }
+}
+
+/// Bluestein's chirp z-transform, expressing a DFT of any length as a power of two convolution.
+fn bluestein(data: &mut [Complex]) {
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+
+    // w_k = exp(-i pi k^2 / n), with k^2 reduced mod 2n to keep the angle exact
+    let chirp: Vec<Complex> = (0..n as u128)
+        .map(|k| Complex::from_angle(-PI * ((k * k) % (2 * n as u128)) as f64 / n as f64))
+        .collect();
+
+    let mut a = vec![Complex::default(); m];
+    for ((a, &x), &w) in a.iter_mut().zip(data.iter()).zip(chirp.iter()) {
+        *a = x * w;
+    }
+
+    let mut b = vec![Complex::default(); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (a, &b) in a.iter_mut().zip(b.iter()) {
+        *a = *a * b;
+    }
+    radix2(&mut a, true);
+
+    let scale = 1.0 / m as f64;
+    for ((x, &a), &w) in data.iter_mut().zip(a.iter()).zip(chirp.iter()) {
+        *x = a * w * Complex::new(scale, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_dft(data: &[Complex]) -> Vec<Complex> {
+        let n = data.len();
+        (0..n)
+            .map(|k| {
+                data.iter().enumerate().fold(Complex::default(), |sum, (j, &x)| {
+                    sum + x * Complex::from_angle(-2.0 * PI * ((j * k) % n) as f64 / n as f64)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_naive_dft() {
+        let mut state = 1u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f64 / u32::MAX as f64 - 0.5
+        };
+
+        // Powers of two go through radix-2, the rest through Bluestein's algorithm
+        for n in (1..=33).chain([64, 100, 128, 257, 1000, 1024]) {
+            let data: Vec<Complex> = (0..n).map(|_| Complex::new(next(), next())).collect();
+            let expected = naive_dft(&data);
+            let mut transformed = data.clone();
+            fft(&mut transformed);
+            for (k, (x, e)) in transformed.iter().zip(expected.iter()).enumerate() {
+                assert!((*x - *e).abs() < 1e-12 * n as f64, "n = {}, X_{} = {:?}, expected {:?}", n, k, x, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::collections::VecDeque;
+
+use serde::Deserialize;
+
+use crate::Onod;
+
+/// How the bytes of an input file encode the samples.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Decoding {
+    /// Raw binary bytes.
+    #[default]
+    Binary,
+    /// Hexadecimal text, two digits per byte.
+    Hex,
+    /// ASCII '0' and '1' characters, eight per byte, most significant bit first.
+    Bits,
+}
+
+impl std::str::FromStr for Decoding {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "binary" => Ok(Decoding::Binary),
+            "hex" => Ok(Decoding::Hex),
+            "bits" => Ok(Decoding::Bits),
+            _ => Err(format!("Unknown input decoding '{}'", name)),
+        }
+    }
+}
+
+/// Streaming decoder for text encoded input. Whitespace between digits is ignored.
+pub struct Decoder {
+    decoding: Decoding,
+    byte: u8,
+    digits: u8,
+}
+
+impl Decoder {
+    pub fn new(decoding: Decoding) -> Self {
+        Decoder { decoding, byte: 0, digits: 0 }
+    }
+
+    /// Decodes the next chunk and appends the resulting bytes to `out`.
+    pub fn process(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> Result<(), String> {
+        let (radix, digits_per_byte) = match self.decoding {
+            Decoding::Binary => {
+                out.extend_from_slice(chunk);
+                return Ok(());
+            }
+            Decoding::Hex => (16, 2),
+            Decoding::Bits => (2, 8),
+        };
+
+        for &c in chunk {
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+            let digit = (c as char)
+                .to_digit(radix)
+                .ok_or_else(|| format!("Invalid character {:?} in {:?} input", c as char, self.decoding))?;
+            self.byte = self.byte.wrapping_mul(radix as u8) | digit as u8;
+            self.digits += 1;
+            if self.digits == digits_per_byte {
+                out.push(self.byte);
+                self.byte = 0;
+                self.digits = 0;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Which part of the decoded input is tested.
+///
+/// The region starts `offset` bytes in and ends `length` bytes later or `skip_suffix` bytes
+/// before the end of the input, whichever comes first. Within the region the input is split
+/// into words of `word` bytes and the first of every `stride` words is kept.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Selection {
+    pub offset: u64,
+    pub length: Option<u64>,
+    pub skip_suffix: u64,
+    pub stride: u64,
+    pub word: usize,
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection { offset: 0, length: None, skip_suffix: 0, stride: 1, word: 1 }
+    }
+}
+
+impl Selection {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.stride == 0 || self.word == 0 {
+            return Err("stride and word size must be at least 1".to_string());
+        }
+        if self.skip_suffix > usize::MAX as u64 {
+            return Err("skip_suffix is too large".to_string());
+        }
+        Ok(())
+    }
+
+    /// True if every input byte is selected.
+    pub fn is_everything(&self) -> bool {
+        *self == Selection::default()
+    }
+}
+
+/// Which bytes a selection ended up covering.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SelectionReport {
+    /// Decoded input bytes seen in total.
+    pub input_bytes: u64,
+    /// First byte of the region.
+    pub start: u64,
+    /// One past the last byte of the region.
+    pub end: u64,
+    /// Bytes passed on after striding; a trailing incomplete word is dropped.
+    pub selected_bytes: u64,
+}
+
+/// Streaming form of a selection. The last `skip_suffix` bytes are held back
+/// until it is known whether they are the end of the input.
+pub struct Selector {
+    selection: Selection,
+    position: u64,
+    held: VecDeque<u8>,
+    released: u64,
+    word: Vec<u8>,
+    word_index: u64,
+    selected: u64,
+}
+
+impl Selector {
+    pub fn new(selection: Selection) -> Self {
+        Selector {
+            selection,
+            position: 0,
+            held: VecDeque::new(),
+            released: 0,
+            word: Vec::with_capacity(selection.word),
+            word_index: 0,
+            selected: 0,
+        }
+    }
+
+    /// Selects from the next chunk and appends the kept bytes to `out`.
+    pub fn process(&mut self, chunk: &[u8], out: &mut Vec<u8>) {
+        let start = self.selection.offset;
+        let end = self.selection.length.map_or(u64::MAX, |length| start.saturating_add(length));
+
+        // Only the part of the chunk that lies between offset and offset + length matters
+        let chunk_start = self.position;
+        self.position += chunk.len() as u64;
+        let from = start.saturating_sub(chunk_start).min(chunk.len() as u64) as usize;
+        let to = end.saturating_sub(chunk_start).min(chunk.len() as u64) as usize;
+        if from < to {
+            self.held.extend(&chunk[from..to]);
+        }
+
+        // A byte is past the suffix once more than `skip_suffix` bytes follow it
+        while !self.held.is_empty()
+            && start.saturating_add(self.released).saturating_add(self.selection.skip_suffix) < self.position
+        {
+            let byte = self.held.pop_front().unwrap();
+            self.release(byte, out);
+        }
+    }
+
+    fn release(&mut self, byte: u8, out: &mut Vec<u8>) {
+        self.released += 1;
+        self.word.push(byte);
+        if self.word.len() == self.selection.word {
+            if self.word_index.is_multiple_of(self.selection.stride) {
+                out.extend_from_slice(&self.word);
+                self.selected += self.word.len() as u64;
+            }
+            self.word_index += 1;
+            self.word.clear();
+        }
+    }
+
+    pub fn report(&self) -> SelectionReport {
+        let start = self.selection.offset.min(self.position);
+        SelectionReport {
+            input_bytes: self.position,
+            start,
+            end: start + self.released,
+            selected_bytes: self.selected,
+        }
+    }
+}
+
+impl Onod {
+    /// Returns the bytes of `samples` picked by `selection`, with a description of the region.
+    pub fn select(samples: &[u8], selection: &Selection) -> (Vec<u8>, SelectionReport) {
+        let mut selector = Selector::new(*selection);
+        let mut out = Vec::new();
+        selector.process(samples, &mut out);
+        (out, selector.report())
+    }
+}
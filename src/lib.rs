@@ -30,30 +30,129 @@ pub struct Onod;
 
 mod uniformity;
 pub mod chisquaretest;
+pub mod conditioning;
+pub mod entropy;
+pub mod fft;
+pub use onod3000_core::health;
+pub mod input;
+pub mod params;
+pub mod profile;
+pub mod second_level;
+pub mod stream;
+pub mod transform;
 pub mod well19937c;
 pub mod ffi;
 #[cfg(feature = "python")]
 pub mod python;
 
+pub use uniformity::cumulative_sums::CusumMode;
+pub use uniformity::fips::{FipsBlock, FipsTest, FIPS_BLOCK_BITS};
+pub use uniformity::linear_complexity::BerlekampMassey;
+pub use uniformity::matrix_rank::{DIEHARD_MATRIX, DIEHARD_SMALL_MATRIX, NIST_MATRIX};
+
+/// Tests of the original ent3000 battery, in report order.
+pub const ENT3000_TESTS: [&str; 15] = [
+    "shannon", "monobit", "chi_bit", "chi_byte", "mean_byte", "compression", "ks", "pi",
+    "shells", "gaps", "avalanche", "runs", "run_ups", "prediction", "uncorrelation",
+];
+
+/// Tests of NIST SP 800-22 in the order of its reports, with monobit as its frequency test.
+pub const NIST_TESTS: [&str; 17] = [
+    "monobit", "block_frequency", "cusum", "cusum_backward", "nist_runs", "longest_run", "matrix_rank", "dft",
+    "non_overlapping_template", "overlapping_template", "universal", "approximate_entropy", "random_excursions",
+    "random_excursions_variant", "serial", "serial2", "linear_complexity",
+];
+
+/// Tests of FIPS 140-2 on consecutive 20,000-bit blocks, followed by all four together.
+pub const FIPS_TESTS: [&str; 5] = ["fips_monobit", "fips_poker", "fips_runs", "fips_long_run", "fips140"];
+
 impl Onod {
+    /// Display name of a test in reports.
+    pub fn test_name(test: &str) -> &str {
+        match test {
+            "approximate_entropy" => "ApproxEntropy",
+            "avalanche"     => "Avalanche",
+            "block_frequency" => "BlockFrequency",
+            "chi_bit"       => "ChiBit",
+            "chi_byte"      => "ChiByte",
+            "compression"   => "Compression",
+            "cusum"         => "CuSum",
+            "cusum_backward" => "CuSumBackward",
+            "dft"           => "DFT",
+            "fips140"       => "FIPS 140-2",
+            "fips_long_run" => "FIPS LongRun",
+            "fips_monobit"  => "FIPS Monobit",
+            "fips_poker"    => "FIPS Poker",
+            "fips_runs"     => "FIPS Runs",
+            "gaps"          => "Gaps",
+            "ks"            => "Kolm.-Smirnov",
+            "linear_complexity" => "LinComplexity",
+            "longest_run"   => "LongestRun",
+            "matrix_rank"   => "MatrixRank",
+            "matrix_rank_31" => "MatrixRank31",
+            "matrix_rank_6x8" => "MatrixRank6x8",
+            "mean_byte"     => "MeanByte",
+            "monobit"       => "Monobit",
+            "nist_runs"     => "NIST Runs",
+            "non_overlapping_template" => "NonOverlapTmpl",
+            "overlapping_template" => "OverlapTmpl",
+            "pi"            => "Pi",
+            "prediction"    => "Prediction",
+            "random_excursions" => "RandExcursions",
+            "random_excursions_variant" => "RandExcVariant",
+            "runs"          => "Runs",
+            "run_ups"       => "RunUps",
+            "serial"        => "Serial",
+            "serial2"       => "Serial2",
+            "shannon"       => "Shannon",
+            "shells"        => "Shells",
+            "uncorrelation" => "UnCorrelation",
+            "universal"     => "Universal",
+            _ => test,
+        }
+    }
+
     pub fn run(test: &str, samples: &[u8]) -> (f64, f64, f64) {
 
         match test {
+            "approximate_entropy" => Onod::approximate_entropy(samples),
             "avalanche"     => Onod::avalanche(samples),
+            "block_frequency" => Onod::block_frequency(samples),
             "chi_bit"       => Onod::chi_bit(samples),
             "chi_byte"      => Onod::chi_byte(samples),
             "compression"   => Onod::compression(samples),
+            "cusum"         => Onod::cumulative_sums(samples, CusumMode::Forward),
+            "cusum_backward" => Onod::cumulative_sums(samples, CusumMode::Backward),
+            "dft"           => Onod::dft(samples),
+            "fips140"       => Onod::fips140(samples),
+            "fips_long_run" => Onod::fips_long_run(samples),
+            "fips_monobit"  => Onod::fips_monobit(samples),
+            "fips_poker"    => Onod::fips_poker(samples),
+            "fips_runs"     => Onod::fips_runs(samples),
             "gaps"          => Onod::gaps(samples),
             "ks"            => Onod::ks(samples),
+            "linear_complexity" => Onod::linear_complexity(samples),
+            "longest_run"   => Onod::longest_run(samples),
+            "matrix_rank"   => Onod::matrix_rank(samples, NIST_MATRIX.0, NIST_MATRIX.1),
+            "matrix_rank_31" => Onod::matrix_rank(samples, DIEHARD_MATRIX.0, DIEHARD_MATRIX.1),
+            "matrix_rank_6x8" => Onod::matrix_rank(samples, DIEHARD_SMALL_MATRIX.0, DIEHARD_SMALL_MATRIX.1),
             "mean_byte"     => Onod::mean_byte(samples),
             "monobit"       => Onod::monobit(samples),
+            "nist_runs"     => Onod::nist_runs(samples),
+            "non_overlapping_template" => Onod::non_overlapping_template(samples),
+            "overlapping_template" => Onod::overlapping_template(samples),
             "pi"            => Onod::pi(samples),
             "prediction"    => Onod::prediction(samples),
+            "random_excursions" => Onod::random_excursions(samples),
+            "random_excursions_variant" => Onod::random_excursions_variant(samples),
             "runs"          => Onod::runs(samples),
             "run_ups"       => Onod::run_ups(samples),
+            "serial"        => Onod::serial(samples).0,
+            "serial2"       => Onod::serial(samples).1,
             "shannon"       => Onod::shannon(samples),
             "shells"        => Onod::shells(samples),
             "uncorrelation" => Onod::uncorrelation(samples),
+            "universal"     => Onod::universal(samples),
             _ => {
                 eprintln!("Error: Unknown test '{}'", test);
                 (-1.0, 0.0, 0.0)
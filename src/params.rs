@@ -0,0 +1,74 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::collections::BTreeMap;
+
+/// Named numeric parameters of a test, e.g. a block size or a seed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params {
+    values: BTreeMap<String, f64>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Params::default()
+    }
+
+    pub fn set(&mut self, name: &str, value: f64) -> &mut Self {
+        self.values.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values.get(name).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Fails on the first parameter the test does not know.
+    pub fn check(&self, test: &str, known: &[&str]) -> Result<(), String> {
+        match self.values.keys().find(|name| !known.contains(&name.as_str())) {
+            Some(name) => Err(format!("Test '{}' has no parameter '{}'", test, name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the parameter as a whole number of at least `min`, or `default` if it is not set.
+    pub fn usize_or(&self, name: &str, default: usize, min: usize) -> Result<usize, String> {
+        match self.get(name) {
+            None => Ok(default),
+            Some(value) if value.fract() == 0.0 && value >= min as f64 && value <= usize::MAX as f64 => Ok(value as usize),
+            Some(value) => Err(format!("Parameter '{}' must be a whole number >= {}, got {}", name, min, value)),
+        }
+    }
+}
+
+impl From<BTreeMap<String, f64>> for Params {
+    fn from(values: BTreeMap<String, f64>) -> Self {
+        Params { values }
+    }
+}
@@ -0,0 +1,392 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{Onod, ENT3000_TESTS, FIPS_TESTS, NIST_TESTS};
+use crate::conditioning::{parse_hex, ConditioningFunction, Conditioner};
+use crate::input::{Decoder, Decoding, Selection, SelectionReport, Selector};
+use crate::params::Params;
+use crate::stream::{Battery, TestResult, CHUNK_SIZE};
+use crate::transform::{Pipeline, Transform, TransformStats};
+
+/// Multiple testing correction applied to the significance level of a suite.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Correction {
+    /// Every test is compared against alpha.
+    #[default]
+    None,
+    /// Every test is compared against alpha / k.
+    Bonferroni,
+    /// Every test is compared against 1 - (1 - alpha)^(1/k).
+    Sidak,
+    /// Holm's step-down procedure.
+    Holm,
+}
+
+impl std::str::FromStr for Correction {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "none" => Ok(Correction::None),
+            "bonferroni" => Ok(Correction::Bonferroni),
+            "sidak" => Ok(Correction::Sidak),
+            "holm" => Ok(Correction::Holm),
+            _ => Err(format!("Unknown correction '{}'", name)),
+        }
+    }
+}
+
+/// Outcome of a single test in a suite.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    Skip,
+}
+
+impl Correction {
+    /// Decides which results pass at family-wise level `alpha`.
+    /// Skipped results (observation of -1) are left out of the family size k.
+    pub fn verdicts(&self, results: &[TestResult], alpha: f64) -> Vec<Verdict> {
+        let tested: Vec<usize> = (0..results.len()).filter(|&i| results[i].0 != -1.0).collect();
+        let k = tested.len() as f64;
+
+        let mut verdicts = vec![Verdict::Skip; results.len()];
+        let mut judge = |i: usize, threshold: f64| {
+            verdicts[i] = if results[i].2 >= threshold { Verdict::Pass } else { Verdict::Fail };
+        };
+
+        match self {
+            Correction::None => tested.iter().for_each(|&i| judge(i, alpha)),
+            Correction::Bonferroni => tested.iter().for_each(|&i| judge(i, alpha / k)),
+            Correction::Sidak => tested.iter().for_each(|&i| judge(i, 1.0 - (1.0 - alpha).powf(1.0 / k))),
+            Correction::Holm => {
+                // Reject in order of increasing p-value until the first acceptance
+                let mut order = tested.clone();
+                order.sort_by(|&a, &b| results[a].2.total_cmp(&results[b].2));
+                let mut rejecting = true;
+                for (rank, &i) in order.iter().enumerate() {
+                    rejecting = rejecting && results[i].2 < alpha / (k - rank as f64);
+                    verdicts[i] = if rejecting { Verdict::Fail } else { Verdict::Pass };
+                }
+            }
+        }
+        verdicts
+    }
+}
+
+fn default_tests() -> Vec<String> {
+    ENT3000_TESTS.iter().map(|test| test.to_string()).collect()
+}
+
+fn default_alpha() -> f64 {
+    0.01
+}
+
+/// A named battery configuration: which tests to run with which parameters,
+/// how to decode and condition the input, and how to judge the results.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Suite {
+    #[serde(default = "default_tests")]
+    pub tests: Vec<String>,
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+    #[serde(default)]
+    pub correction: Correction,
+    #[serde(default)]
+    pub input: Decoding,
+    /// Part of the decoded input to test.
+    #[serde(default)]
+    pub selection: Selection,
+    #[serde(default)]
+    pub transforms: Vec<Transform>,
+    /// Conditioning function spec, `function[:n_in[:n_out]]`.
+    pub condition: Option<String>,
+    /// Hexadecimal key of the conditioning function.
+    pub condition_key: Option<String>,
+    /// Min-entropy per input byte used for the conditioning entropy claim.
+    pub h_in: Option<f64>,
+    /// Per-test parameters, keyed by test name.
+    #[serde(default)]
+    pub params: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl Default for Suite {
+    /// The ent3000 battery on raw binary input at alpha = 0.01.
+    fn default() -> Self {
+        Suite {
+            tests: default_tests(),
+            alpha: default_alpha(),
+            correction: Correction::None,
+            input: Decoding::Binary,
+            selection: Selection::default(),
+            transforms: Vec::new(),
+            condition: None,
+            condition_key: None,
+            h_in: None,
+            params: BTreeMap::new(),
+        }
+    }
+}
+
+/// Summary of the conditioning stage of a suite run.
+#[derive(Clone, Copy, Debug)]
+pub struct ConditioningSummary {
+    pub function: ConditioningFunction,
+    pub blocks: u64,
+    pub input_bits: usize,
+    pub output_bits: usize,
+    /// Output_Entropy per output block, if the suite declares `h_in`.
+    pub output_entropy: Option<f64>,
+}
+
+/// Everything a suite run produced.
+#[derive(Clone, Debug)]
+pub struct SuiteReport {
+    /// Bytes read from the input, before decoding.
+    pub input_bytes: u64,
+    /// Region of the decoded input that was selected for testing.
+    pub selection: SelectionReport,
+    pub transforms: Vec<TransformStats>,
+    pub conditioning: Option<ConditioningSummary>,
+    /// Byte count and results of the battery on the selected input before conditioning,
+    /// present when the suite conditions its input.
+    pub raw: Option<(u64, Vec<TestResult>)>,
+    /// Bytes the battery ran on.
+    pub tested_bytes: u64,
+    pub results: Vec<TestResult>,
+}
+
+/// Names of the built-in suites.
+pub const PRESETS: [&str; 3] = ["ent3000", "nist", "fips140"];
+
+impl Suite {
+    /// Built-in suite of the given name: `ent3000`, the default battery, `nist`, all NIST SP 800-22 tests with
+    /// their default parameters, each judged at alpha = 0.01 as in NIST's reports, or `fips140`, the FIPS 140-2
+    /// tests, which fail when more blocks fail than random bits would.
+    pub fn preset(name: &str) -> Option<Suite> {
+        match name {
+            "ent3000" => Some(Suite::default()),
+            "nist" => Some(Suite { tests: NIST_TESTS.iter().map(|test| test.to_string()).collect(), ..Suite::default() }),
+            "fips140" => Some(Suite { tests: FIPS_TESTS.iter().map(|test| test.to_string()).collect(), ..Suite::default() }),
+            _ => None,
+        }
+    }
+
+    /// Checks tests, parameters, transforms and conditioning settings.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tests.is_empty() {
+            return Err("A suite needs at least one test".to_string());
+        }
+        if !(self.alpha > 0.0 && self.alpha < 1.0) {
+            return Err(format!("alpha must lie between 0 and 1, got {}", self.alpha));
+        }
+        if let Some(name) = self.params.keys().find(|name| !self.tests.contains(name)) {
+            return Err(format!("Parameters given for test '{}' which is not in the suite", name));
+        }
+        self.selection.validate()?;
+        if let Some(h) = self.h_in {
+            if !(0.0..=8.0).contains(&h) {
+                return Err("h_in is the min-entropy per input byte and must lie between 0 and 8".to_string());
+            }
+        }
+        self.battery()?;
+        self.conditioner()?;
+        Ok(())
+    }
+
+    /// Parameters of the given test.
+    pub fn params(&self, test: &str) -> Params {
+        self.params.get(test).cloned().map(Params::from).unwrap_or_default()
+    }
+
+    fn battery(&self) -> Result<Battery, String> {
+        let tests: Vec<&str> = self.tests.iter().map(|test| test.as_str()).collect();
+        let params = self.tests.iter().map(|test| (test.clone(), self.params(test))).collect();
+        Battery::with_params(&tests, &params)
+    }
+
+    /// The conditioner the suite declares, if any, with its key decoded.
+    pub fn conditioner(&self) -> Result<Option<Conditioner>, String> {
+        let key = match &self.condition_key {
+            Some(hex) => parse_hex(hex)?,
+            None => Vec::new(),
+        };
+        match &self.condition {
+            Some(spec) => Ok(Some(Conditioner::from_spec(spec, &key)?)),
+            None if self.condition_key.is_some() => Err("condition_key given without condition".to_string()),
+            None => Ok(None),
+        }
+    }
+
+    /// Pass, fail or skip for each result, with the suite's alpha and correction.
+    pub fn verdicts(&self, results: &[TestResult]) -> Vec<Verdict> {
+        self.correction.verdicts(results, self.alpha)
+    }
+
+    /// Decodes, selects, transforms and conditions everything `reader` yields and runs the battery on it,
+    /// all in a single pass. Memory stays bounded unless the battery holds the non-overlapping template test.
+    pub fn run<R: Read>(&self, mut reader: R) -> io::Result<SuiteReport> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+
+        let mut decoder = Decoder::new(self.input);
+        let mut selector = Selector::new(self.selection);
+        let mut pipeline = Pipeline::new(&self.transforms);
+        let mut conditioner = self.conditioner().map_err(invalid)?;
+        let mut battery = self.battery().map_err(invalid)?;
+        // The unconditioned input is tested alongside for comparison
+        let mut raw_battery = match conditioner {
+            Some(_) => Some(self.battery().map_err(invalid)?),
+            None => None,
+        };
+
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut input_bytes = 0u64;
+        let (mut decoded, mut selected) = (Vec::new(), Vec::new());
+        let (mut transformed, mut conditioned) = (Vec::new(), Vec::new());
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            input_bytes += read as u64;
+
+            decoded.clear();
+            decoder
+                .process(&buffer[..read], &mut decoded)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            selected.clear();
+            selector.process(&decoded, &mut selected);
+
+            if let Some(raw_battery) = raw_battery.as_mut() {
+                raw_battery.update(&selected);
+            }
+
+            transformed.clear();
+            pipeline.process(&selected, &mut transformed);
+            match conditioner.as_mut() {
+                Some(conditioner) => {
+                    conditioned.clear();
+                    conditioner.process(&transformed, &mut conditioned);
+                    battery.update(&conditioned);
+                }
+                None => battery.update(&transformed),
+            }
+        }
+
+        let conditioning = conditioner.map(|conditioner| ConditioningSummary {
+            function: conditioner.function(),
+            blocks: conditioner.blocks(),
+            input_bits: conditioner.input_bits(),
+            output_bits: conditioner.output_bits(),
+            output_entropy: self
+                .h_in
+                .map(|h| conditioner.output_entropy(h * conditioner.input_bits() as f64 / 8.0)),
+        });
+
+        Ok(SuiteReport {
+            input_bytes,
+            selection: selector.report(),
+            transforms: pipeline.stats(),
+            conditioning,
+            raw: raw_battery.map(|mut b| (b.len(), b.finish())),
+            tested_bytes: battery.len(),
+            results: battery.finish(),
+        })
+    }
+
+    /// Reads the whole input and returns it decoded, selected and transformed but not conditioned, for the
+    /// analyses that need all samples at once.
+    pub fn read_samples<R: Read>(&self, mut reader: R) -> io::Result<Vec<u8>> {
+        let mut input = Vec::new();
+        reader.read_to_end(&mut input)?;
+        let mut decoded = Vec::new();
+        Decoder::new(self.input)
+            .process(&input, &mut decoded)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let (selected, _) = Onod::select(&decoded, &self.selection);
+        let mut samples = Vec::new();
+        Pipeline::new(&self.transforms).process(&selected, &mut samples);
+        Ok(samples)
+    }
+}
+
+/// A set of named suites loaded from a TOML file, one `[suite.<name>]` table each.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default)]
+    pub suite: BTreeMap<String, Suite>,
+}
+
+impl Profile {
+    /// Parses and validates a profile.
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let profile: Profile = toml::from_str(text).map_err(|e| e.to_string())?;
+        if profile.suite.is_empty() {
+            return Err("The profile defines no suite".to_string());
+        }
+        for (name, suite) in &profile.suite {
+            suite.validate().map_err(|e| format!("Suite '{}': {}", name, e))?;
+        }
+        Ok(profile)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Profile::from_toml(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Returns the named suite, or the only suite of the profile if no name is given.
+    pub fn suite(&self, name: Option<&str>) -> Result<&Suite, String> {
+        match name {
+            Some(name) => self.suite.get(name).ok_or_else(|| format!("No suite named '{}'", name)),
+            None if self.suite.len() == 1 => Ok(self.suite.values().next().unwrap()),
+            None => Err(format!(
+                "The profile defines several suites, choose one of: {}",
+                self.suite.keys().cloned().collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+}
+
+impl Onod {
+    /// Runs a suite over `samples`.
+    pub fn run_suite(samples: &[u8], suite: &Suite) -> io::Result<SuiteReport> {
+        suite.run(samples)
+    }
+}
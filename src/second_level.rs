@@ -0,0 +1,156 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::distribution::{ContinuousCDF, Normal};
+
+use crate::Onod;
+use crate::chisquaretest::igamc;
+use crate::stream::TestResult;
+
+/// Significance level of the individual p-values when counting passes, as in NIST SP 800-22.
+pub const SECOND_LEVEL_ALPHA: f64 = 0.01;
+
+/// Second-level analysis of a set of p-values (NIST SP 800-22, 4.2).
+///
+/// Under the null hypothesis the p-values are uniform on [0, 1] and each one passes with
+/// probability 1 - alpha. Both are checked: the uniformity with a chi-square over ten equal
+/// bins and the proportion of passes against its three sigma range. Either failing fails the result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SecondLevel {
+    pub count: usize,
+    pub passed: usize,
+    /// p-values per bin [0, 0.1), [0.1, 0.2), ... [0.9, 1]
+    pub histogram: [u64; 10],
+    pub chi_square: f64,
+    /// p-value of the uniformity of the p-values
+    pub uniformity: f64,
+    pub alpha: f64,
+}
+
+impl SecondLevel {
+    /// Analyses the p-values, counting those of at least `alpha` as passed.
+    pub fn new(p_values: &[f64], alpha: f64) -> Self {
+        let mut histogram = [0u64; 10];
+        for &p in p_values {
+            histogram[((p * 10.0).floor().max(0.0) as usize).min(9)] += 1;
+        }
+
+        let expected = p_values.len() as f64 / 10.0;
+        let chi_square = match p_values.len() {
+            0 => 0.0,
+            _ => histogram.iter().map(|&f| (f as f64 - expected).powi(2) / expected).sum(),
+        };
+
+        SecondLevel {
+            count: p_values.len(),
+            passed: p_values.iter().filter(|&&p| p >= alpha).count(),
+            histogram,
+            chi_square,
+            // chi^2 has 9 degrees of freedom, and is 0 for no p-values or perfectly flat ones
+            uniformity: igamc(4.5, chi_square / 2.0),
+            alpha,
+        }
+    }
+
+    /// Observed proportion of passed p-values.
+    pub fn proportion(&self) -> f64 {
+        self.passed as f64 / self.count as f64
+    }
+
+    /// Proportions of passes within three standard deviations of 1 - alpha.
+    pub fn proportion_range(&self) -> (f64, f64) {
+        let p = 1.0 - self.alpha;
+        let margin = 3.0 * (p * self.alpha / self.count as f64).sqrt();
+        (p - margin, (p + margin).min(1.0))
+    }
+
+    pub fn proportion_ok(&self) -> bool {
+        let (low, high) = self.proportion_range();
+        (low..=high).contains(&self.proportion())
+    }
+
+    /// Proportion of passes, its z-score and the p-value of the uniformity of the p-values. A proportion outside
+    /// its three sigma range caps the p-value at its own two-tailed p-value, below 0.0027.
+    pub fn result(&self) -> TestResult {
+
+        if self.count == 0 {
+            return (-1.0, 0.0, 1.0); // Nothing to analyse
+        }
+
+        let p = 1.0 - self.alpha;
+        let z_score = (self.proportion() - p) / (p * self.alpha / self.count as f64).sqrt();
+        let p_value = match self.proportion_ok() {
+            true => self.uniformity,
+            false => {
+                let normal_dist = Normal::new(0.0, 1.0).expect("Failed to create Normal distribution");
+                self.uniformity.min(2.0 * (1.0 - normal_dist.cdf(z_score.abs())))
+            }
+        };
+        (self.proportion(), z_score, p_value)
+    }
+}
+
+impl Onod {
+    /// Second-level analysis of several results of one test, ignoring skipped ones.
+    pub fn second_level(results: &[TestResult]) -> TestResult {
+        let p_values: Vec<f64> = results.iter().filter(|r| r.0 != -1.0).map(|r| r.2).collect();
+        SecondLevel::new(&p_values, SECOND_LEVEL_ALPHA).result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_or_flat_p_values_pass() {
+        let none = SecondLevel::new(&[], SECOND_LEVEL_ALPHA);
+        assert_eq!((none.uniformity, none.result()), (1.0, (-1.0, 0.0, 1.0)));
+
+        let flat: Vec<f64> = (0..10).map(|bin| bin as f64 / 10.0 + 0.05).collect();
+        let second_level = SecondLevel::new(&flat, SECOND_LEVEL_ALPHA);
+        assert_eq!((second_level.chi_square, second_level.uniformity), (0.0, 1.0));
+        assert_eq!(second_level.result().2, 1.0);
+
+        for len in [0, 1, 7] {
+            assert_eq!(Onod::run("non_overlapping_template", &vec![0xa7; len]), (-1.0, 0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn proportion_out_of_range_fails() {
+        // Flat p-values, but every one in the lowest bin falls below alpha: 90% pass where 99% are expected
+        let p_values: Vec<f64> = (0..1000).map(|i| match i % 10 {
+            0 => 0.005,
+            bin => bin as f64 / 10.0 + 0.05,
+        }).collect();
+        let second_level = SecondLevel::new(&p_values, SECOND_LEVEL_ALPHA);
+        assert_eq!(second_level.uniformity, 1.0);
+        assert!(!second_level.proportion_ok());
+        let (proportion, z_score, p_value) = second_level.result();
+        assert_eq!(proportion, 0.9);
+        assert!(z_score < -3.0 && p_value < 0.0027, "z = {}, p = {}", z_score, p_value);
+    }
+}
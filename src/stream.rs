@@ -0,0 +1,446 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+
+use crate::Onod;
+use crate::params::Params;
+use crate::uniformity::{
+    avalanche::{Avalanche, XOR_WINDOW_SIZE},
+    block_frequency::{BlockFrequency, BLOCK_FREQUENCY_BLOCK_SIZE},
+    chi_bit::ChiBit, chi_byte::ChiByte, compression::CompressionRatio,
+    cumulative_sums::{CumulativeSums, CusumMode},
+    dft::Dft,
+    fips::{Fips, FipsTest},
+    gaps::Gaps, ks::Ks,
+    linear_complexity::{LinearComplexity, LINEAR_COMPLEXITY_BLOCK_SIZE},
+    longest_run::LongestRun,
+    matrix_rank::{MatrixRank, DIEHARD_MATRIX, DIEHARD_SMALL_MATRIX, NIST_MATRIX},
+    mean_byte::MeanByte, monobit::Monobit, nist_runs::NistRuns, pi::Pi, prediction::Prediction,
+    random_excursions::RandomExcursions,
+    runs::Runs, runups::RunUps,
+    serial::{ApproximateEntropy, Serial, MAX_APPROXIMATE_ENTROPY_LENGTH, MAX_SERIAL_LENGTH},
+    shannon::Shannon, shells::Shells,
+    template_matching::{
+        aperiodic_templates, OverlappingTemplate, MAX_TEMPLATE_LENGTH, NON_OVERLAPPING_BLOCKS,
+        OVERLAPPING_BLOCK_SIZE, TEMPLATE_LENGTH,
+    },
+    uncorrelation::UnCorrelation,
+    universal::{Universal, MAX_BLOCK_LENGTH, MIN_BLOCK_LENGTH},
+};
+
+/// Statistic, z-score and p-value of a single test.
+pub type TestResult = (f64, f64, f64);
+
+/// Size of the buffer used when pulling samples from a reader.
+pub const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Incremental form of a randomness test.
+///
+/// Samples are fed in arbitrarily sized chunks and the result is identical to
+/// running the slice based test over the concatenation of all chunks.
+pub trait Accumulator {
+    /// Feeds the next chunk of samples.
+    fn update(&mut self, chunk: &[u8]);
+
+    /// Completes the test and returns the statistic, z-score and p-value over all samples fed.
+    /// No more samples should be fed afterwards.
+    fn finish(&mut self) -> (f64, f64, f64);
+}
+
+/// Reassembles fixed size blocks that straddle chunk boundaries.
+pub(crate) struct Blocks {
+    size: usize,
+    pending: Vec<u8>,
+}
+
+impl Blocks {
+    pub(crate) fn new(size: usize) -> Self {
+        Blocks { size, pending: Vec::with_capacity(size) }
+    }
+
+    /// Calls `f` on every complete block in `chunk`, carrying any incomplete tail over to the next call.
+    pub(crate) fn feed(&mut self, mut chunk: &[u8], mut f: impl FnMut(&[u8])) {
+        if !self.pending.is_empty() {
+            let needed = (self.size - self.pending.len()).min(chunk.len());
+            self.pending.extend_from_slice(&chunk[..needed]);
+            chunk = &chunk[needed..];
+            if self.pending.len() < self.size {
+                return;
+            }
+            f(&self.pending);
+            self.pending.clear();
+        }
+        let mut blocks = chunk.chunks_exact(self.size);
+        for block in &mut blocks {
+            f(block);
+        }
+        self.pending.extend_from_slice(blocks.remainder());
+    }
+
+    /// The incomplete block carried over so far.
+    pub(crate) fn remainder(&self) -> &[u8] {
+        &self.pending
+    }
+}
+
+/// Slice based form of a test.
+type SliceTest = dyn Fn(&[u8]) -> TestResult;
+
+/// Collects every sample for tests that need the whole sequence at once.
+pub(crate) struct Buffered {
+    samples: Vec<u8>,
+    test: Box<SliceTest>,
+}
+
+impl Buffered {
+    pub(crate) fn new(test: impl Fn(&[u8]) -> TestResult + 'static) -> Self {
+        Buffered { samples: Vec::new(), test: Box::new(test) }
+    }
+}
+
+impl Accumulator for Buffered {
+    fn update(&mut self, chunk: &[u8]) {
+        self.samples.extend_from_slice(chunk);
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+        let samples = std::mem::take(&mut self.samples);
+        (self.test)(&samples)
+    }
+}
+
+impl Onod {
+    /// Returns a fresh accumulator for the named test, or `None` if the test is unknown.
+    pub fn accumulator(test: &str) -> Option<Box<dyn Accumulator>> {
+        Onod::accumulator_with(test, &Params::new()).ok()
+    }
+
+    /// Returns a fresh accumulator for the named test configured with `params`.
+    /// Fails if the test is unknown or a parameter does not apply to it.
+    pub fn accumulator_with(test: &str, params: &Params) -> Result<Box<dyn Accumulator>, String> {
+        let accumulator: Box<dyn Accumulator> = match test {
+            "approximate_entropy" => {
+                params.check(test, &["m"])?;
+                Box::new(ApproximateEntropy::new(length_param(params, 1, MAX_APPROXIMATE_ENTROPY_LENGTH)?))
+            }
+            "avalanche" => {
+                params.check(test, &["window"])?;
+                Box::new(Avalanche::new(params.usize_or("window", XOR_WINDOW_SIZE, 1)?))
+            }
+            "block_frequency" => {
+                params.check(test, &["block_size"])?;
+                Box::new(BlockFrequency::new(params.usize_or("block_size", BLOCK_FREQUENCY_BLOCK_SIZE, 1)?))
+            }
+            "cusum" => {
+                params.check(test, &["mode"])?;
+                match params.usize_or("mode", 0, 0)? {
+                    0 => Box::new(CumulativeSums::new(CusumMode::Forward)),
+                    1 => Box::new(CumulativeSums::new(CusumMode::Backward)),
+                    mode => return Err(format!("Parameter 'mode' must be 0 (forward) or 1 (backward), got {}", mode)),
+                }
+            }
+            "ks" => {
+                params.check(test, &["seed"])?;
+                match params.get("seed") {
+                    Some(_) => match params.usize_or("seed", 0, 0)? {
+                        seed if seed <= u32::MAX as usize => Box::new(Ks::with_seed(seed as u32)),
+                        seed => return Err(format!("Parameter 'seed' must be between 0 and {}, got {}", u32::MAX, seed)),
+                    },
+                    None => Box::new(Ks::default()),
+                }
+            }
+            "linear_complexity" => {
+                params.check(test, &["block_size"])?;
+                Box::new(LinearComplexity::new(params.usize_or("block_size", LINEAR_COMPLEXITY_BLOCK_SIZE, 2)?))
+            }
+            "longest_run" => {
+                params.check(test, &["block_size"])?;
+                match params.get("block_size") {
+                    None => Box::new(LongestRun::default()),
+                    Some(_) => {
+                        let block_size = params.usize_or("block_size", 0, 1)?;
+                        if !LongestRun::block_sizes().any(|m| m == block_size) {
+                            return Err(format!("Parameter 'block_size' must be 8, 128 or 10000, got {}", block_size));
+                        }
+                        Box::new(LongestRun::new(Some(block_size)))
+                    }
+                }
+            }
+            "matrix_rank" => {
+                params.check(test, &["rows", "cols"])?;
+                let rows = params.usize_or("rows", NIST_MATRIX.0, 2)?;
+                let cols = params.usize_or("cols", NIST_MATRIX.1, 2)?;
+                if rows > 64 || cols > 64 {
+                    return Err(format!("Matrices of {}x{} bits exceed the limit of 64x64", rows, cols));
+                }
+                Box::new(MatrixRank::new(rows, cols))
+            }
+            "non_overlapping_template" => {
+                params.check(test, &["m", "blocks", "template"])?;
+                let m = params.usize_or("m", TEMPLATE_LENGTH, 1)?;
+                let blocks = params.usize_or("blocks", NON_OVERLAPPING_BLOCKS, 1)?;
+                let templates = match template_param(params, m)? {
+                    Some(template) => vec![template],
+                    None => aperiodic_templates(m),
+                };
+                Box::new(Buffered::new(move |samples| {
+                    let results = Onod::non_overlapping_templates(samples, &templates, m, blocks);
+                    match results.len() {
+                        1 => results[0],
+                        _ => Onod::second_level(&results),
+                    }
+                }))
+            }
+            "overlapping_template" => {
+                params.check(test, &["m", "block_size", "template"])?;
+                let m = params.usize_or("m", TEMPLATE_LENGTH, 1)?;
+                let block_size = params.usize_or("block_size", OVERLAPPING_BLOCK_SIZE, m)?;
+                let template = template_param(params, m)?.unwrap_or((1 << m) - 1);
+                Box::new(OverlappingTemplate::new(template, m, block_size))
+            }
+            "random_excursions" | "random_excursions_variant" => {
+                params.check(test, &["state"])?;
+                let excursions = RandomExcursions::new(test == "random_excursions_variant", None);
+                let state = match params.get("state") {
+                    None => None,
+                    Some(x) if x.fract() == 0.0 && x != 0.0 && x.abs() <= excursions.max_state() as f64 => Some(x as i64),
+                    Some(x) => return Err(format!(
+                        "Parameter 'state' must be a non-zero whole number between -{max} and {max}, got {}",
+                        x, max = excursions.max_state()
+                    )),
+                };
+                Box::new(RandomExcursions::new(test == "random_excursions_variant", state))
+            }
+            "serial" | "serial2" => {
+                params.check(test, &["m"])?;
+                let difference = if test == "serial" { 1 } else { 2 };
+                Box::new(Serial::new(length_param(params, 2, MAX_SERIAL_LENGTH)?, difference))
+            }
+            "universal" => {
+                params.check(test, &["block_length", "init_blocks"])?;
+                let block_length = match params.get("block_length") {
+                    None => None,
+                    Some(_) => match params.usize_or("block_length", 0, MIN_BLOCK_LENGTH)? {
+                        length if length <= MAX_BLOCK_LENGTH => Some(length),
+                        length => return Err(format!(
+                            "Parameter 'block_length' must be between {} and {}, got {}",
+                            MIN_BLOCK_LENGTH, MAX_BLOCK_LENGTH, length
+                        )),
+                    },
+                };
+                let init_blocks = match params.get("init_blocks") {
+                    None => None,
+                    Some(_) => Some(params.usize_or("init_blocks", 0, 1)? as u64),
+                };
+                if init_blocks.is_some() && block_length.is_none() {
+                    return Err("Parameter 'init_blocks' needs 'block_length' to be set as well".to_string());
+                }
+                Box::new(Universal::new(block_length, init_blocks))
+            }
+            _ => {
+                params.check(test, &[])?;
+                match test {
+                    "chi_bit"       => Box::new(ChiBit::default()),
+                    "chi_byte"      => Box::new(ChiByte::default()),
+                    "compression"   => Box::new(CompressionRatio::default()),
+                    "cusum_backward" => Box::new(CumulativeSums::new(CusumMode::Backward)),
+                    "dft"           => Box::new(Dft::default()),
+                    "fips140"       => Box::new(Fips::new(FipsTest::All)),
+                    "fips_long_run" => Box::new(Fips::new(FipsTest::LongRun)),
+                    "fips_monobit"  => Box::new(Fips::new(FipsTest::Monobit)),
+                    "fips_poker"    => Box::new(Fips::new(FipsTest::Poker)),
+                    "fips_runs"     => Box::new(Fips::new(FipsTest::Runs)),
+                    "gaps"          => Box::new(Gaps::default()),
+                    "matrix_rank_31" => Box::new(MatrixRank::new(DIEHARD_MATRIX.0, DIEHARD_MATRIX.1)),
+                    "matrix_rank_6x8" => Box::new(MatrixRank::new(DIEHARD_SMALL_MATRIX.0, DIEHARD_SMALL_MATRIX.1)),
+                    "mean_byte"     => Box::new(MeanByte::default()),
+                    "monobit"       => Box::new(Monobit::default()),
+                    "nist_runs"     => Box::new(NistRuns::default()),
+                    "pi"            => Box::new(Pi::default()),
+                    "prediction"    => Box::new(Prediction::default()),
+                    "runs"          => Box::new(Runs::default()),
+                    "run_ups"       => Box::new(RunUps::default()),
+                    "shannon"       => Box::new(Shannon::default()),
+                    "shells"        => Box::new(Shells::default()),
+                    "uncorrelation" => Box::new(UnCorrelation::default()),
+                    _ => return Err(format!("Unknown test '{}'", test)),
+                }
+            }
+        };
+        Ok(accumulator)
+    }
+
+    /// Runs the named test over `samples` with the given parameters.
+    pub fn run_with(test: &str, samples: &[u8], params: &Params) -> Result<TestResult, String> {
+        let mut accumulator = Onod::accumulator_with(test, params)?;
+        accumulator.update(samples);
+        Ok(accumulator.finish())
+    }
+
+    /// Runs a single test over everything `reader` yields, in bounded memory.
+    pub fn run_reader<R: Read>(test: &str, reader: R) -> io::Result<TestResult> {
+        let (_, results) = Onod::run_battery_reader(&[test], reader)?;
+        Ok(results[0])
+    }
+
+    /// Runs several tests in a single pass over `reader`.
+    /// Returns the number of bytes read together with one result per test, in order.
+    pub fn run_battery_reader<R: Read>(tests: &[&str], mut reader: R) -> io::Result<(u64, Vec<TestResult>)> {
+        let mut battery = Battery::new(tests).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            battery.update(&buffer[..read]);
+        }
+
+        Ok((battery.len(), battery.finish()))
+    }
+}
+
+/// The optional pattern length parameter `m`, between `min` and `max`.
+fn length_param(params: &Params, min: usize, max: usize) -> Result<Option<usize>, String> {
+    match params.get("m") {
+        None => Ok(None),
+        Some(_) => match params.usize_or("m", 0, min)? {
+            m if m <= max => Ok(Some(m)),
+            m => Err(format!("Parameter 'm' must be between {} and {}, got {}", min, max, m)),
+        },
+    }
+}
+
+/// The `template` parameter as an `m`-bit pattern, after checking `m` is supported.
+fn template_param(params: &Params, m: usize) -> Result<Option<u64>, String> {
+    if m > MAX_TEMPLATE_LENGTH {
+        return Err(format!("Parameter 'm' must be at most {}, got {}", MAX_TEMPLATE_LENGTH, m));
+    }
+    match params.get("template") {
+        None => Ok(None),
+        Some(_) => {
+            let template = params.usize_or("template", 0, 0)? as u64;
+            if template >> m != 0 {
+                return Err(format!("Parameter 'template' must fit in m = {} bits, got {}", m, template));
+            }
+            Ok(Some(template))
+        }
+    }
+}
+
+/// A set of accumulators fed with the same samples.
+pub struct Battery {
+    accumulators: Vec<Box<dyn Accumulator>>,
+    len: u64,
+}
+
+impl Battery {
+    /// Creates accumulators for the named tests, failing on the first unknown name.
+    pub fn new(tests: &[&str]) -> Result<Self, String> {
+        Battery::with_params(tests, &BTreeMap::new())
+    }
+
+    /// Like `new`, with the parameters of each test looked up by its name.
+    pub fn with_params(tests: &[&str], params: &BTreeMap<String, Params>) -> Result<Self, String> {
+        let no_params = Params::new();
+        let accumulators = tests
+            .iter()
+            .map(|&test| Onod::accumulator_with(test, params.get(test).unwrap_or(&no_params)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Battery { accumulators, len: 0 })
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.len += chunk.len() as u64;
+        for accumulator in self.accumulators.iter_mut() {
+            accumulator.update(chunk);
+        }
+    }
+
+    /// Number of bytes fed so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Completes every test and returns the results in the order the tests were given.
+    pub fn finish(&mut self) -> Vec<TestResult> {
+        self.accumulators.iter_mut().map(|a| a.finish()).collect()
+    }
+}
+
+/// Helpers shared by the accumulators' tests.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+
+    /// First 1,000,000 bits of the binary expansion of e, the sample data of NIST's test suite that most examples
+    /// of SP 800-22 and its Appendix B are worked on.
+    pub(crate) const E: &[u8] = include_bytes!("../test/e.bin");
+
+    /// `len` pseudo-random bytes from a xorshift generator.
+    pub(crate) fn xorshift_bytes(len: usize) -> Vec<u8> {
+        let mut state = 1u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    /// Checks that feeding odd-sized chunks, which straddle any block boundary, gives the one-shot result, both
+    /// with and without an incomplete tail of at most 119 bytes.
+    pub(crate) fn assert_chunking_invariant<A: Accumulator>(new: impl Fn() -> A) {
+        let samples = xorshift_bytes(100_003);
+        for samples in [&samples[..], &samples[..99_960]] {
+            let mut one_shot = new();
+            one_shot.update(samples);
+            let expected = one_shot.finish();
+
+            let mut chunked = new();
+            let mut rest = samples;
+            for size in [1, 7, 13, 31, 257, 1001, 3].iter().cycle() {
+                if rest.is_empty() {
+                    break;
+                }
+                let (chunk, tail) = rest.split_at((*size).min(rest.len()));
+                chunked.update(chunk);
+                rest = tail;
+            }
+            assert_eq!(chunked.finish(), expected, "{} bytes", samples.len());
+        }
+    }
+}
@@ -0,0 +1,396 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use std::fmt;
+use std::io::{self, Read};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::Onod;
+use crate::stream::CHUNK_SIZE;
+
+/// Number of bits Peres extraction works on at a time.
+const PERES_BLOCK_BITS: usize = 4096;
+
+/// Default recursion depth of the Peres extractor, enough to exhaust a full block.
+const PERES_DEFAULT_DEPTH: u32 = 12;
+
+/// Bit-level conditioning transforms. Bits are taken most significant first
+/// and the output is repacked into bytes the same way.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(try_from = "String")]
+pub enum Transform {
+    /// Von Neumann debiasing: 01 -> 0, 10 -> 1, 00 and 11 are dropped.
+    VonNeumann,
+    /// Iterated von Neumann (Peres) with the given recursion depth, over 4096-bit blocks.
+    Peres(u32),
+    /// Outputs the parity of every n-bit block, i.e. XORs n consecutive bits into one. `xor_fold:n` is an alias.
+    Parity(usize),
+    /// Keeps every k-th bit.
+    Decimate(usize),
+}
+
+impl FromStr for Transform {
+    type Err = String;
+
+    /// Parses `name[:parameter]`, e.g. `von_neumann`, `peres:4`, `parity:8`, `decimate:3`.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = match spec.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (spec, None),
+        };
+        let parameter = |default: Option<usize>| -> Result<usize, String> {
+            let value = match (argument, default) {
+                (Some(p), _) => p.parse::<usize>().map_err(|_| format!("Invalid parameter in transform '{}'", spec))?,
+                (None, Some(d)) => d,
+                (None, None) => return Err(format!("Transform '{}' needs a parameter, e.g. '{}:2'", name, name)),
+            };
+            if value == 0 {
+                return Err(format!("Parameter of transform '{}' must be at least 1", spec));
+            }
+            Ok(value)
+        };
+
+        match name {
+            "von_neumann" | "vn" => match argument {
+                None => Ok(Transform::VonNeumann),
+                Some(_) => Err(format!("Transform '{}' takes no parameter", name)),
+            },
+            "peres" => Ok(Transform::Peres(parameter(Some(PERES_DEFAULT_DEPTH as usize))? as u32)),
+            "parity" | "xor_fold" => Ok(Transform::Parity(parameter(None)?)),
+            "decimate" => Ok(Transform::Decimate(parameter(None)?)),
+            _ => Err(format!("Unknown transform '{}'", name)),
+        }
+    }
+}
+
+impl TryFrom<String> for Transform {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, Self::Error> {
+        spec.parse()
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transform::VonNeumann => write!(f, "von_neumann"),
+            Transform::Peres(depth) => write!(f, "peres:{}", depth),
+            Transform::Parity(n) => write!(f, "parity:{}", n),
+            Transform::Decimate(k) => write!(f, "decimate:{}", k),
+        }
+    }
+}
+
+/// Parses a comma separated list of transforms.
+pub fn parse_transforms(specs: &str) -> Result<Vec<Transform>, String> {
+    specs.split(',').map(|spec| spec.trim().parse()).collect()
+}
+
+/// Packs bits into bytes, most significant bit first.
+#[derive(Default)]
+struct BitPacker {
+    byte: u8,
+    bits: u8,
+}
+
+impl BitPacker {
+    fn push(&mut self, bit: bool, out: &mut Vec<u8>) {
+        self.byte = (self.byte << 1) | bit as u8;
+        self.bits += 1;
+        if self.bits == 8 {
+            out.push(self.byte);
+            self.byte = 0;
+            self.bits = 0;
+        }
+    }
+}
+
+/// One transform together with its streaming state and throughput counters.
+struct Stage {
+    transform: Transform,
+    packer: BitPacker,
+    // Von Neumann: first bit of the current pair
+    pending_bit: Option<bool>,
+    // Parity and decimation: position in the current block and running parity
+    position: usize,
+    parity: bool,
+    // Peres: bits of the current block
+    block: Vec<bool>,
+    bits_in: u64,
+    bits_out: u64,
+}
+
+impl Stage {
+    fn new(transform: Transform) -> Self {
+        Stage {
+            transform,
+            packer: BitPacker::default(),
+            pending_bit: None,
+            position: 0,
+            parity: false,
+            block: Vec::new(),
+            bits_in: 0,
+            bits_out: 0,
+        }
+    }
+
+    fn emit(&mut self, bit: bool, out: &mut Vec<u8>) {
+        self.packer.push(bit, out);
+        self.bits_out += 1;
+    }
+
+    fn process(&mut self, input: &[u8], out: &mut Vec<u8>) {
+        self.bits_in += 8 * input.len() as u64;
+
+        for &byte in input {
+            for shift in (0..8).rev() {
+                self.process_bit((byte >> shift) & 1 == 1, out);
+            }
+        }
+    }
+
+    fn process_bit(&mut self, bit: bool, out: &mut Vec<u8>) {
+        match self.transform {
+            Transform::VonNeumann => match self.pending_bit.take() {
+                Some(first) if first != bit => self.emit(first, out),
+                Some(_) => {}
+                None => self.pending_bit = Some(bit),
+            },
+            Transform::Peres(depth) => {
+                self.block.push(bit);
+                if self.block.len() == PERES_BLOCK_BITS {
+                    let block = std::mem::take(&mut self.block);
+                    let mut extracted = Vec::new();
+                    peres(&block, depth, &mut extracted);
+                    for bit in extracted {
+                        self.emit(bit, out);
+                    }
+                    self.block = block;
+                    self.block.clear();
+                }
+            }
+            Transform::Parity(n) => {
+                self.parity ^= bit;
+                self.position += 1;
+                if self.position == n {
+                    let parity = self.parity;
+                    self.emit(parity, out);
+                    self.parity = false;
+                    self.position = 0;
+                }
+            }
+            Transform::Decimate(k) => {
+                if self.position == 0 {
+                    self.emit(bit, out);
+                }
+                self.position = (self.position + 1) % k;
+            }
+        }
+    }
+}
+
+/// Peres' iterated von Neumann extractor.
+/// Emits the von Neumann bits of `bits`, then recurses into the XORs of all pairs
+/// and into the values of the equal pairs.
+fn peres(bits: &[bool], depth: u32, out: &mut Vec<bool>) {
+    if depth == 0 || bits.len() < 2 {
+        return;
+    }
+
+    let mut xors = Vec::with_capacity(bits.len() / 2);
+    let mut equal = Vec::new();
+    for pair in bits.chunks_exact(2) {
+        if pair[0] != pair[1] {
+            out.push(pair[0]);
+        } else {
+            equal.push(pair[0]);
+        }
+        xors.push(pair[0] ^ pair[1]);
+    }
+
+    peres(&xors, depth - 1, out);
+    peres(&equal, depth - 1, out);
+}
+
+/// Input-to-output accounting of one transform.
+#[derive(Clone, Copy, Debug)]
+pub struct TransformStats {
+    pub transform: Transform,
+    pub bits_in: u64,
+    pub bits_out: u64,
+}
+
+impl TransformStats {
+    /// Number of input bits consumed per output bit.
+    pub fn ratio(&self) -> f64 {
+        self.bits_in as f64 / self.bits_out as f64
+    }
+}
+
+/// Chain of transforms applied in order. Incomplete bytes and blocks are held back
+/// between calls and dropped at the end of the stream.
+pub struct Pipeline {
+    stages: Vec<Stage>,
+}
+
+impl Pipeline {
+    pub fn new(transforms: &[Transform]) -> Self {
+        Pipeline { stages: transforms.iter().map(|&t| Stage::new(t)).collect() }
+    }
+
+    /// Transforms the next chunk of input and appends the resulting bytes to `out`.
+    pub fn process(&mut self, chunk: &[u8], out: &mut Vec<u8>) {
+        let mut input = chunk.to_vec();
+        for stage in self.stages.iter_mut() {
+            let mut output = Vec::with_capacity(input.len());
+            stage.process(&input, &mut output);
+            input = output;
+        }
+        out.extend_from_slice(&input);
+    }
+
+    pub fn stats(&self) -> Vec<TransformStats> {
+        self.stages
+            .iter()
+            .map(|stage| TransformStats {
+                transform: stage.transform,
+                bits_in: stage.bits_in,
+                bits_out: stage.bits_out,
+            })
+            .collect()
+    }
+}
+
+/// Reader adapter that runs everything read from `inner` through a pipeline.
+pub struct TransformReader<R: Read> {
+    inner: R,
+    pipeline: Pipeline,
+    buffer: Vec<u8>,
+    output: Vec<u8>,
+    consumed: usize,
+}
+
+impl<R: Read> TransformReader<R> {
+    pub fn new(inner: R, transforms: &[Transform]) -> Self {
+        TransformReader {
+            inner,
+            pipeline: Pipeline::new(transforms),
+            buffer: vec![0u8; CHUNK_SIZE],
+            output: Vec::new(),
+            consumed: 0,
+        }
+    }
+
+    pub fn stats(&self) -> Vec<TransformStats> {
+        self.pipeline.stats()
+    }
+}
+
+impl<R: Read> Read for TransformReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.consumed == self.output.len() {
+            let read = self.inner.read(&mut self.buffer)?;
+            if read == 0 {
+                return Ok(0);
+            }
+            self.output.clear();
+            self.consumed = 0;
+            self.pipeline.process(&self.buffer[..read], &mut self.output);
+        }
+
+        let n = buf.len().min(self.output.len() - self.consumed);
+        buf[..n].copy_from_slice(&self.output[self.consumed..self.consumed + n]);
+        self.consumed += n;
+        Ok(n)
+    }
+}
+
+impl Onod {
+    /// Applies the transforms in order to `samples` and returns the conditioned bytes with per-transform statistics.
+    pub fn transform(samples: &[u8], transforms: &[Transform]) -> (Vec<u8>, Vec<TransformStats>) {
+        let mut pipeline = Pipeline::new(transforms);
+        let mut out = Vec::new();
+        pipeline.process(samples, &mut out);
+        (out, pipeline.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(samples: &[u8], spec: &str) -> (Vec<u8>, TransformStats) {
+        let (out, stats) = Onod::transform(samples, &parse_transforms(spec).unwrap());
+        (out, stats[0])
+    }
+
+    #[test]
+    fn von_neumann_keeps_the_first_bit_of_unequal_pairs() {
+        // 01 10 00 11 | 10 10 01 01 | 01 01 00 00
+        let (out, stats) = transform(&[0b0110_0011, 0b1010_0101, 0b0101_0000], "von_neumann");
+        assert_eq!(out, [0b0111_0000]);
+        assert_eq!((stats.bits_in, stats.bits_out, stats.ratio()), (24, 8, 3.0));
+    }
+
+    #[test]
+    fn peres_recurses_into_xors_and_equal_pairs() {
+        // Every byte holds the pairs 01 10 00 11: von Neumann gives 0 1, the XORs 1 1 0 0 give nothing
+        // and the equal pairs' values 0 1 give 0 one level down
+        let block = [0b0110_0011; PERES_BLOCK_BITS / 8];
+        let (out, stats) = transform(&block, "peres:2");
+        assert_eq!(out, [&[0x55; 128][..], &[0x00; 64]].concat());
+        assert_eq!((stats.bits_in, stats.bits_out), (4096, 1536));
+        assert_eq!(stats.ratio(), 8.0 / 3.0);
+
+        // A single level is plain von Neumann, and an incomplete block is held back
+        let (out, stats) = transform(&[&block[..], &block[..100]].concat(), "peres:1");
+        assert_eq!(out, [0x55; 128]);
+        assert_eq!((stats.bits_in, stats.bits_out), (4896, 1024));
+    }
+
+    #[test]
+    fn parity_xors_blocks_of_bits() {
+        // 111 000 000 000 011 111 111 111
+        let samples = [0b1110_0000, 0b0000_0111, 0b1111_1111];
+        let (out, stats) = transform(&samples, "parity:3");
+        assert_eq!(out, [0b1000_0111]);
+        assert_eq!((stats.bits_in, stats.bits_out, stats.ratio()), (24, 8, 3.0));
+
+        assert_eq!("xor_fold:3".parse::<Transform>(), Ok(Transform::Parity(3)));
+        assert_eq!(Transform::Parity(3).to_string(), "parity:3");
+        assert_eq!(transform(&samples, "xor_fold:3").0, out);
+    }
+
+    #[test]
+    fn decimate_keeps_every_kth_bit() {
+        // Bits 0, 2, .., 22 of 11100000 00000111 11111111 are 110000 011111, the last 4 wait for more input
+        let (out, stats) = transform(&[0b1110_0000, 0b0000_0111, 0b1111_1111], "decimate:2");
+        assert_eq!(out, [0b1100_0001]);
+        assert_eq!((stats.bits_in, stats.bits_out, stats.ratio()), (24, 12, 2.0));
+    }
+}
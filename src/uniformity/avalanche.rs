@@ -26,43 +26,85 @@
 use statrs::distribution::{Normal, ContinuousCDF};
 
 use crate::Onod;
-
-impl Onod {
-
-    /// Avalanche randomness test
-    /// Compares the bit-level differences between consecutive chunks of data and returns a p-value.
-    pub fn avalanche(samples: &[u8]) -> (f64, f64, f64) {
-
-        const XOR_WINDOW_SIZE: usize = 20; // Bytes. Equivalent to SHA-1 (160 bits).
-
-        if samples.len() < 2 * XOR_WINDOW_SIZE {
-            return (-1.0, 0.0, 1.0); // Not enough data for meaningful calculation
+use crate::stream::{Accumulator, Blocks};
+
+pub const XOR_WINDOW_SIZE: usize = 20; // Bytes. Equivalent to SHA-1 (160 bits).
+
+/// Streaming state of the avalanche test.
+pub struct Avalanche {
+    window_size: usize,
+    windows: Blocks,
+    position: u64,
+    window_pairs: u64,
+    // Changed bits of the latest window pair, only counted once more data follows it
+    pending: Option<u32>,
+    sum: f64,
+    count: u64,
+}
+
+impl Default for Avalanche {
+    fn default() -> Self {
+        Avalanche::new(XOR_WINDOW_SIZE)
+    }
+}
+
+impl Avalanche {
+    /// Compares consecutive windows of `window_size` bytes.
+    pub fn new(window_size: usize) -> Self {
+        Avalanche {
+            window_size,
+            windows: Blocks::new(2 * window_size),
+            position: 0,
+            window_pairs: 0,
+            pending: None,
+            sum: 0.0,
+            count: 0,
         }
-
-        let mut means = Vec::new();
-
-        for i in (0..samples.len() - (2 * XOR_WINDOW_SIZE)).step_by(2 * XOR_WINDOW_SIZE) {
-            let a_start = i;
-            let a_end = i + XOR_WINDOW_SIZE;
-            let b_start = a_end;
-            let b_end = b_start + XOR_WINDOW_SIZE;
-
-            let a_bytes = &samples[a_start..a_end];
-            let b_bytes = &samples[b_start..b_end];
+    }
+}
+
+impl Accumulator for Avalanche {
+    fn update(&mut self, chunk: &[u8]) {
+        let window_size = self.window_size;
+        let (pending, sum, count, window_pairs) =
+            (&mut self.pending, &mut self.sum, &mut self.count, &mut self.window_pairs);
+
+        self.windows.feed(chunk, |pair| {
+            if let Some(changed_bits) = pending.take() {
+                *sum += changed_bits as f64;
+                *count += 1;
+            }
 
             // XOR the two chunks and count differing bits
+            let (a_bytes, b_bytes) = pair.split_at(window_size);
             let mut changed_bits = 0;
             for (a, b) in a_bytes.iter().zip(b_bytes.iter()) {
                 changed_bits += (a ^ b).count_ones();
             }
 
-            means.push(changed_bits as f64);
+            *pending = Some(changed_bits);
+            *window_pairs += 1;
+        });
+
+        self.position += chunk.len() as u64;
+        if self.position > self.window_pairs * (2 * self.window_size) as u64 {
+            if let Some(changed_bits) = self.pending.take() {
+                self.sum += changed_bits as f64;
+                self.count += 1;
+            }
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if self.count == 0 {
+            return (-1.0, 0.0, 1.0); // Not enough data for meaningful calculation
         }
 
         // Calculate the mean and standard deviation of bit differences
-        let mean_observed = means.iter().sum::<f64>() / means.len() as f64;
-        let mean_ref = (XOR_WINDOW_SIZE * 8) as f64 / 2.0; // Expected mean bits
-        let std_dev_ref = 0.5 * ((XOR_WINDOW_SIZE * 8) as f64).sqrt(); // Expected standard deviation
+        let mean_observed = self.sum / self.count as f64;
+        let mean_ref = (self.window_size * 8) as f64 / 2.0; // Expected mean bits
+        let std_dev_ref = 0.5 * ((self.window_size * 8) as f64).sqrt(); // Expected standard deviation
 
         // Calculate Z score
         let z_score = (mean_observed - mean_ref) / std_dev_ref;
@@ -73,5 +115,28 @@ impl Onod {
 
         (mean_observed, z_score, p_value)
     }
+}
+
+impl Onod {
+
+    /// Avalanche randomness test
+    /// Compares the bit-level differences between consecutive chunks of data and returns a p-value.
+    pub fn avalanche(samples: &[u8]) -> (f64, f64, f64) {
+        let mut avalanche = Avalanche::default();
+        avalanche.update(samples);
+        avalanche.finish()
+    }
+
+}
 
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::assert_chunking_invariant;
+
+    #[test]
+    fn chunked_input_matches_one_shot() {
+        assert_chunking_invariant(Avalanche::default);
+        assert_chunking_invariant(|| Avalanche::new(7));
+    }
+}
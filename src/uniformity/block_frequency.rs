@@ -0,0 +1,126 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use crate::Onod;
+use crate::chisquaretest::igamc;
+use crate::stream::Accumulator;
+
+/// Block size M used when none is given, as recommended by NIST SP 800-22.
+pub const BLOCK_FREQUENCY_BLOCK_SIZE: usize = 128;
+
+/// Streaming state of the NIST SP 800-22 frequency test within a block.
+pub struct BlockFrequency {
+    block_size: usize,
+    ones: u64,
+    bits: usize,
+    blocks: u64,
+    // Sum of (2 * ones - M)^2 over all complete blocks
+    deviations: f64,
+}
+
+impl Default for BlockFrequency {
+    fn default() -> Self {
+        BlockFrequency::new(BLOCK_FREQUENCY_BLOCK_SIZE)
+    }
+}
+
+impl BlockFrequency {
+    /// Splits the bit sequence into blocks of `block_size` bits, the NIST parameter M.
+    pub fn new(block_size: usize) -> Self {
+        BlockFrequency { block_size, ones: 0, bits: 0, blocks: 0, deviations: 0.0 }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        self.ones += bit as u64;
+        self.bits += 1;
+        if self.bits == self.block_size {
+            let deviation = 2.0 * self.ones as f64 - self.block_size as f64;
+            self.deviations += deviation * deviation;
+            self.blocks += 1;
+            self.ones = 0;
+            self.bits = 0;
+        }
+    }
+}
+
+impl Accumulator for BlockFrequency {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                self.push_bit((byte >> shift) & 1 == 1);
+            }
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if self.blocks == 0 {
+            return (-1.0, 0.0, 1.0); // Not a single complete block, bits left over are discarded
+        }
+
+        // chi^2 = 4M * sum((pi_i - 1/2)^2) with pi_i the proportion of ones in block i
+        let n = self.blocks as f64;
+        let chi_square = self.deviations / self.block_size as f64;
+
+        // chi^2 has N degrees of freedom
+        let z_score = (chi_square - n) / (2.0 * n).sqrt();
+        let p_value = igamc(n / 2.0, chi_square / 2.0);
+
+        (chi_square, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Frequency test within a block (NIST SP 800-22, 2.2)
+    /// Checks that the proportion of 1s in each block of M bits is close to 1/2 and returns a p-value.
+    pub fn block_frequency(samples: &[u8]) -> (f64, f64, f64) {
+        let mut block_frequency = BlockFrequency::default();
+        block_frequency.update(samples);
+        block_frequency.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_nist_example() {
+        // Example of SP 800-22 section 2.2.8
+        let mut block_frequency = BlockFrequency::new(3);
+        "0110011010".chars().for_each(|bit| block_frequency.push_bit(bit == '1'));
+        let (chi_square, _, p_value) = block_frequency.finish();
+        assert!((chi_square - 1.0).abs() < 1e-12, "chi^2 = {}", chi_square);
+        assert!((p_value - 0.801252).abs() < 1e-6, "p = {}", p_value);
+    }
+
+    #[test]
+    fn balanced_blocks_pass() {
+        // Alternating bits fill every block with exactly half ones, a chi-square statistic of 0
+        let (chi_square, _, p_value) = Onod::block_frequency(&[0x55; 1000]);
+        assert_eq!((chi_square, p_value), (0.0, 1.0));
+    }
+}
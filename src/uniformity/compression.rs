@@ -23,34 +23,83 @@
 // SOFTWARE.
 
 
+use bzip2::write::BzEncoder;
 use flate2::{write::DeflateEncoder, Compression};
-use std::io::Write;
+use std::io::{self, Write};
 use statrs::distribution::{Normal, ContinuousCDF};
 
 use crate::Onod;
+use crate::stream::Accumulator;
 
-impl Onod {
+/// Writer that only counts the bytes the encoder produces.
+#[derive(Default)]
+struct ByteCounter(u64);
 
-    /// Compression randomness test
-    /// Estimates randomness by the compressibility of the data and returns a p-value.
-    pub fn compression(samples: &[u8]) -> (f64, f64, f64) {
-        if samples.is_empty() {
-            return (-1.0, 0.0, 1.0); // Perfect randomness for empty data
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Length in bytes of `data` compressed with bzip2 at the 500k block size of the SP 800-90B reference implementation.
+pub(crate) fn bzipped_len(data: &[u8]) -> u64 {
+    let mut encoder = BzEncoder::new(ByteCounter::default(), bzip2::Compression::new(5));
+    encoder.write_all(data).expect("Counting never fails");
+    encoder.finish().expect("Counting never fails").0
+}
+
+/// Streaming state of the compression test.
+pub struct CompressionRatio {
+    encoder: Option<DeflateEncoder<ByteCounter>>,
+    original_size: u64,
+    failed: bool,
+}
+
+impl Default for CompressionRatio {
+    fn default() -> Self {
+        CompressionRatio {
+            encoder: Some(DeflateEncoder::new(ByteCounter::default(), Compression::default())),
+            original_size: 0,
+            failed: false,
         }
+    }
+}
 
+impl Accumulator for CompressionRatio {
+    fn update(&mut self, chunk: &[u8]) {
         // Compress the data using deflate
-        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
-        if encoder.write_all(samples).is_err() {
-            return (-1.0, 0.0, 1.0); // Compression failed
+        match self.encoder.as_mut() {
+            Some(encoder) => {
+                if encoder.write_all(chunk).is_err() {
+                    self.failed = true;
+                }
+            }
+            None => self.failed = true, // Already finished
         }
-        let compressed_data = match encoder.finish() {
-            Ok(data) => data,
-            Err(_) => return (-1.0, 0.0, 1.0), // Compression failed
+        self.original_size += chunk.len() as u64;
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+        if self.original_size == 0 || self.failed {
+            return (-1.0, 0.0, 1.0); // Perfect randomness for empty data
+        }
+
+        let compressed_size = match self.encoder.take().map(|encoder| encoder.finish()) {
+            Some(Ok(counter)) => counter.0,
+            _ => {
+                self.failed = true;
+                return (-1.0, 0.0, 1.0); // Compression failed
+            }
         };
 
         // Calculate compression ratio
-        let original_size = samples.len() as f64;
-        let compressed_size = compressed_data.len() as f64;
+        let original_size = self.original_size as f64;
+        let compressed_size = compressed_size as f64;
         let compression_ratio = compressed_size / original_size;
 
         // Z-score calculation
@@ -64,4 +113,26 @@ impl Onod {
 
         (compression_ratio, z_score, p_value)
     }
-}
\ No newline at end of file
+}
+
+impl Onod {
+
+    /// Compression randomness test
+    /// Estimates randomness by the compressibility of the data and returns a p-value.
+    pub fn compression(samples: &[u8]) -> (f64, f64, f64) {
+        let mut compression = CompressionRatio::default();
+        compression.update(samples);
+        compression.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::assert_chunking_invariant;
+
+    #[test]
+    fn chunked_input_matches_one_shot() {
+        assert_chunking_invariant(CompressionRatio::default);
+    }
+}
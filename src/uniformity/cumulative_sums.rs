@@ -0,0 +1,151 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::distribution::{Normal, ContinuousCDF};
+
+use crate::Onod;
+use crate::stream::Accumulator;
+
+/// Direction in which the partial sums are accumulated, NIST's `mode` parameter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CusumMode {
+    /// mode = 0, from the first bit to the last.
+    #[default]
+    Forward,
+    /// mode = 1, from the last bit to the first.
+    Backward,
+}
+
+/// Streaming state of the NIST SP 800-22 cumulative sums test.
+#[derive(Default)]
+pub struct CumulativeSums {
+    mode: CusumMode,
+    bits: u64,
+    sum: i64,
+    // Extremes of the partial sums S_0 = 0 .. S_n
+    min: i64,
+    max: i64,
+    // Extremes of S_0 .. S_(n-1), which bound the backward sums S_n - S_k
+    min_before: i64,
+    max_before: i64,
+}
+
+impl CumulativeSums {
+    pub fn new(mode: CusumMode) -> Self {
+        CumulativeSums { mode, ..CumulativeSums::default() }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        self.min_before = self.min_before.min(self.sum);
+        self.max_before = self.max_before.max(self.sum);
+
+        // Bits map to -1 and +1
+        self.sum += 2 * bit as i64 - 1;
+        self.min = self.min.min(self.sum);
+        self.max = self.max.max(self.sum);
+        self.bits += 1;
+    }
+}
+
+impl Accumulator for CumulativeSums {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                self.push_bit((byte >> shift) & 1 == 1);
+            }
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if self.bits == 0 {
+            return (-1.0, 0.0, 1.0); // Default to perfect randomness for empty data
+        }
+
+        // Largest excursion of the partial sums from zero
+        let z = match self.mode {
+            CusumMode::Forward => self.max.max(-self.min),
+            CusumMode::Backward => (self.sum - self.min_before).max(self.max_before - self.sum),
+        } as f64;
+
+        let n = self.bits as f64;
+        let sqrt_n = n.sqrt();
+        let normal_dist = Normal::new(0.0, 1.0).expect("Failed to create Normal distribution");
+        let phi = |x: f64| normal_dist.cdf(x * z / sqrt_n);
+
+        // Summation bounds are truncated towards zero as in the NIST reference implementation
+        let mut p_value = 1.0;
+        for k in ((-n / z + 1.0) / 4.0) as i64..=((n / z - 1.0) / 4.0) as i64 {
+            let k = k as f64;
+            p_value -= phi(4.0 * k + 1.0) - phi(4.0 * k - 1.0);
+        }
+        for k in ((-n / z - 3.0) / 4.0) as i64..=((n / z - 1.0) / 4.0) as i64 {
+            let k = k as f64;
+            p_value += phi(4.0 * k + 3.0) - phi(4.0 * k + 1.0);
+        }
+
+        (z, z / sqrt_n, p_value.clamp(0.0, 1.0))
+    }
+}
+
+impl Onod {
+
+    /// Cumulative sums test (NIST SP 800-22, 2.13)
+    /// Checks how far the random walk of +1/-1 mapped bits strays from zero, forward or backward, and returns a p-value.
+    pub fn cumulative_sums(samples: &[u8], mode: CusumMode) -> (f64, f64, f64) {
+        let mut cumulative_sums = CumulativeSums::new(mode);
+        cumulative_sums.update(samples);
+        cumulative_sums.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example of SP 800-22 section 2.13.8
+    const EPSILON: &str = "1100100100001111110110101010001000100001011010001100001000110100\
+                           110001001100011001100010100010111000";
+
+    fn cumulative_sums(bits: &str, mode: CusumMode) -> (f64, f64, f64) {
+        let mut cumulative_sums = CumulativeSums::new(mode);
+        bits.chars().for_each(|bit| cumulative_sums.push_bit(bit == '1'));
+        cumulative_sums.finish()
+    }
+
+    #[test]
+    fn forward_matches_nist_example() {
+        let (z, _, p_value) = cumulative_sums(EPSILON, CusumMode::Forward);
+        assert_eq!(z, 16.0);
+        assert!((p_value - 0.219194).abs() < 1e-6, "p = {}", p_value);
+    }
+
+    #[test]
+    fn backward_matches_nist_example() {
+        let (z, _, p_value) = cumulative_sums(EPSILON, CusumMode::Backward);
+        assert_eq!(z, 19.0);
+        assert!((p_value - 0.114866).abs() < 1e-6, "p = {}", p_value);
+    }
+}
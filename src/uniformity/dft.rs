@@ -0,0 +1,148 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::distribution::{Normal, ContinuousCDF};
+
+use crate::Onod;
+use crate::fft::{fft, Complex};
+use crate::stream::{Accumulator, Blocks, TestResult};
+
+/// Bits of each sequence the DFT test transforms, a power of two so the FFT needs no padding.
+pub const DFT_SEQUENCE_BITS: usize = 1 << 20;
+
+/// Streaming state of the DFT test.
+///
+/// The input is cut into sequences of `DFT_SEQUENCE_BITS` bits that are tested one at a time, so memory stays at
+/// about 16 bytes per bit of one sequence. Several sequences are combined by a second-level analysis. Input
+/// shorter than one sequence is tested as a whole, a shorter tail after full sequences is left out.
+pub struct Dft {
+    sequences: Blocks,
+    results: Vec<TestResult>,
+}
+
+impl Default for Dft {
+    fn default() -> Self {
+        Dft { sequences: Blocks::new(DFT_SEQUENCE_BITS / 8), results: Vec::new() }
+    }
+}
+
+impl Accumulator for Dft {
+    fn update(&mut self, chunk: &[u8]) {
+        let results = &mut self.results;
+        self.sequences.feed(chunk, |sequence| results.push(spectral_test(sequence)));
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+        match self.results.len() {
+            0 => spectral_test(self.sequences.remainder()),
+            1 => self.results[0],
+            _ => Onod::second_level(&self.results),
+        }
+    }
+}
+
+/// Spectral test of one sequence: the number of peaks of the +1/-1 mapped bits below the 95% threshold.
+fn spectral_test(samples: &[u8]) -> TestResult {
+    spectral_test_bits(samples.iter().flat_map(|&byte| (0..8).rev().map(move |shift| (byte >> shift) & 1 == 1)))
+}
+
+fn spectral_test_bits(bits: impl Iterator<Item = bool>) -> TestResult {
+
+    let mut spectrum: Vec<Complex> = bits.map(|bit| Complex::new(if bit { 1.0 } else { -1.0 }, 0.0)).collect();
+    let n = spectrum.len();
+    if n == 0 {
+        return (-1.0, 0.0, 1.0); // Default to perfect randomness for empty data
+    }
+    fft(&mut spectrum);
+
+    // 95% of the moduli of the first n/2 frequencies lie below T for random data
+    let n = n as f64;
+    let threshold = ((1.0 / 0.05f64).ln() * n).sqrt();
+    let expected_peaks = 0.95 * n / 2.0;
+    let observed_peaks = spectrum[..spectrum.len() / 2]
+        .iter()
+        .filter(|s| s.abs() < threshold)
+        .count() as f64;
+
+    let z_score = (observed_peaks - expected_peaks) / (n * 0.95 * 0.05 / 4.0).sqrt();
+    let normal_dist = Normal::new(0.0, 1.0).expect("Failed to create Normal distribution");
+    let p_value = 2.0 * (1.0 - normal_dist.cdf(z_score.abs()));
+
+    (observed_peaks, z_score, p_value)
+}
+
+impl Onod {
+
+    /// Discrete Fourier transform (spectral) test (NIST SP 800-22, 2.6)
+    /// Counts the spectral peaks of the +1/-1 mapped bits that exceed the 95% threshold and returns a p-value.
+    /// Inputs longer than 2^20 bits are tested per sequence of 2^20 bits and combined by a second-level analysis.
+    pub fn dft(samples: &[u8]) -> (f64, f64, f64) {
+        let mut dft = Dft::default();
+        dft.update(samples);
+        dft.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::E;
+
+    #[test]
+    fn matches_nist_examples() {
+        // Section 2.6.8: 100 bits, a length the FFT handles with Bluestein's algorithm. The example prints N1 = 46,
+        // d = -1.376494 and P = 0.168669, but only 2 of the 50 moduli, 18.73 and 20.85, exceed T = 17.31, so N1 = 48.
+        let epsilon = "1100100100001111110110101010001000100001011010001100001000110100\
+                       110001001100011001100010100010111000";
+        let (peaks, z_score, p_value) = spectral_test_bits(epsilon.chars().map(|bit| bit == '1'));
+        assert_eq!(peaks, 48.0);
+        assert!((z_score - 0.5 / (100.0 * 0.95 * 0.05 / 4.0f64).sqrt()).abs() < 1e-12, "d = {}", z_score);
+        assert!((p_value - 0.646355).abs() < 1e-6, "p = {}", p_value);
+
+        // Appendix B: the 1,000,000 bits of e, a single sequence
+        let p_value = Onod::dft(E).2;
+        assert!((p_value - 0.847187).abs() < 1e-6, "p = {}", p_value);
+    }
+
+    #[test]
+    fn combines_sequences_and_leaves_out_the_tail() {
+        let sequence = DFT_SEQUENCE_BITS / 8;
+        let mut state = 1u32;
+        let samples: Vec<u8> = (0..2 * sequence + 1000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 24) as u8
+            })
+            .collect();
+
+        let mut dft = Dft::default();
+        samples.chunks(65_537).for_each(|chunk| dft.update(chunk));
+        let expected = [spectral_test(&samples[..sequence]), spectral_test(&samples[sequence..2 * sequence])];
+        assert_eq!(dft.finish(), Onod::second_level(&expected));
+        assert_eq!(Onod::dft(&samples[..sequence - 1]), spectral_test(&samples[..sequence - 1]));
+    }
+}
@@ -0,0 +1,276 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use crate::Onod;
+use crate::stream::{Accumulator, Blocks};
+use statrs::distribution::{Binomial, DiscreteCDF};
+
+/// Bits in each block of the FIPS 140-2 statistical random number generator tests.
+pub const FIPS_BLOCK_BITS: usize = 20_000;
+const FIPS_BLOCK_BYTES: usize = FIPS_BLOCK_BITS / 8;
+
+// Acceptance intervals of FIPS 140-2, section 4.9.1, inclusive except for the poker statistic
+const MONOBIT_ONES: (u32, u32) = (9726, 10274);
+const POKER: (f64, f64) = (2.16, 46.17);
+const RUNS: [(u32, u32); 6] = [(2343, 2657), (1135, 1365), (542, 708), (251, 373), (111, 201), (111, 201)];
+const LONG_RUN: u32 = 26;
+
+// Probability that a block of random bits fails each test: exact for the monobit and long run tests, from the
+// chi-square distribution with 15 degrees of freedom for the poker test and estimated from 4,000,000 simulated
+// blocks for the runs test
+const MONOBIT_FALSE_ALARM: f64 = 1.035e-4;
+const POKER_FALSE_ALARM: f64 = 9.923e-5;
+const RUNS_FALSE_ALARM: f64 = 3.775e-3;
+const LONG_RUN_FALSE_ALARM: f64 = 2.976e-4;
+
+/// Statistics of one 20,000-bit block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FipsBlock {
+    pub ones: u32,
+    /// Poker statistic X = 16/5000 * sum(f(i)^2) - 5000 over the 5000 4-bit segments.
+    pub poker: f64,
+    /// Number of runs of zeros and of ones of length 1 to 5 and 6 or more.
+    pub runs: [[u32; 6]; 2],
+    pub longest_run: u32,
+}
+
+impl FipsBlock {
+    /// Evaluates a block of 2500 bytes, most significant bit first.
+    pub fn new(block: &[u8]) -> Self {
+        assert_eq!(block.len(), FIPS_BLOCK_BYTES, "FIPS 140-2 blocks have 20,000 bits.");
+
+        let mut segments = [0u32; 16];
+        for &byte in block {
+            segments[(byte >> 4) as usize] += 1;
+            segments[(byte & 0x0f) as usize] += 1;
+        }
+        let squares: u64 = segments.iter().map(|&f| f as u64 * f as u64).sum();
+        let poker = 16.0 / 5000.0 * squares as f64 - 5000.0;
+
+        let mut runs = [[0u32; 6]; 2];
+        let mut longest_run = 0;
+        let (mut bit, mut length) = (block[0] >> 7, 0u32);
+        let mut end_run = |bit: u8, length: u32| {
+            runs[bit as usize][length.min(6) as usize - 1] += 1;
+            longest_run = longest_run.max(length);
+        };
+        for &byte in block {
+            for shift in (0..8).rev() {
+                let next = (byte >> shift) & 1;
+                if next != bit {
+                    end_run(bit, length);
+                    bit = next;
+                    length = 0;
+                }
+                length += 1;
+            }
+        }
+        end_run(bit, length);
+
+        let ones = block.iter().map(|byte| byte.count_ones()).sum();
+        FipsBlock { ones, poker, runs, longest_run }
+    }
+
+    pub fn monobit_passed(&self) -> bool {
+        (MONOBIT_ONES.0..=MONOBIT_ONES.1).contains(&self.ones)
+    }
+
+    pub fn poker_passed(&self) -> bool {
+        self.poker > POKER.0 && self.poker < POKER.1
+    }
+
+    pub fn runs_passed(&self) -> bool {
+        self.runs.iter().all(|counts| counts.iter().zip(RUNS).all(|(count, (lo, hi))| (lo..=hi).contains(count)))
+    }
+
+    pub fn long_run_passed(&self) -> bool {
+        self.longest_run < LONG_RUN
+    }
+
+    /// Whether the block passes all four tests.
+    pub fn passed(&self) -> bool {
+        self.monobit_passed() && self.poker_passed() && self.runs_passed() && self.long_run_passed()
+    }
+}
+
+/// One of the FIPS 140-2 tests, or all of them together.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FipsTest {
+    Monobit,
+    Poker,
+    Runs,
+    LongRun,
+    All,
+}
+
+impl FipsTest {
+    fn passed(&self, block: &FipsBlock) -> bool {
+        match self {
+            FipsTest::Monobit => block.monobit_passed(),
+            FipsTest::Poker => block.poker_passed(),
+            FipsTest::Runs => block.runs_passed(),
+            FipsTest::LongRun => block.long_run_passed(),
+            FipsTest::All => block.passed(),
+        }
+    }
+
+    /// Probability that a block of random bits fails the test, treating the four tests as independent for `All`.
+    pub fn false_alarm_rate(&self) -> f64 {
+        match self {
+            FipsTest::Monobit => MONOBIT_FALSE_ALARM,
+            FipsTest::Poker => POKER_FALSE_ALARM,
+            FipsTest::Runs => RUNS_FALSE_ALARM,
+            FipsTest::LongRun => LONG_RUN_FALSE_ALARM,
+            FipsTest::All => 1.0 - [MONOBIT_FALSE_ALARM, POKER_FALSE_ALARM, RUNS_FALSE_ALARM, LONG_RUN_FALSE_ALARM]
+                .iter().map(|rate| 1.0 - rate).product::<f64>(),
+        }
+    }
+}
+
+/// Streaming state of a FIPS 140-2 test over consecutive 20,000-bit blocks.
+pub struct Fips {
+    test: FipsTest,
+    blocks: Blocks,
+    tested: u64,
+    failed: u64,
+}
+
+impl Fips {
+    pub fn new(test: FipsTest) -> Self {
+        Fips { test, blocks: Blocks::new(FIPS_BLOCK_BYTES), tested: 0, failed: 0 }
+    }
+}
+
+impl Accumulator for Fips {
+    fn update(&mut self, chunk: &[u8]) {
+        let (test, tested, failed) = (self.test, &mut self.tested, &mut self.failed);
+        self.blocks.feed(chunk, |block| {
+            *tested += 1;
+            *failed += !test.passed(&FipsBlock::new(block)) as u64;
+        });
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if self.tested == 0 {
+            return (-1.0, 0.0, 1.0); // Not a single complete block, bits left over are discarded
+        }
+
+        // A single block is judged by the standard's acceptance intervals alone. Over more blocks, the p-value is the
+        // probability of at least as many failed blocks when each fails at the test's false alarm rate.
+        let failure_rate = self.failed as f64 / self.tested as f64;
+        let p_value = if self.tested == 1 {
+            if self.failed == 0 { 1.0 } else { 0.0 }
+        } else if self.failed == 0 {
+            1.0
+        } else {
+            Binomial::new(self.test.false_alarm_rate(), self.tested).unwrap().sf(self.failed - 1)
+        };
+
+        (failure_rate, 0.0, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Monobit test of FIPS 140-2
+    /// Fails a 20,000-bit block unless it holds between 9726 and 10274 ones. Returns the fraction of failed blocks and
+    /// the binomial probability of failing at least as many.
+    pub fn fips_monobit(samples: &[u8]) -> (f64, f64, f64) {
+        Onod::fips(samples, FipsTest::Monobit)
+    }
+
+    /// Poker test of FIPS 140-2
+    /// Fails a 20,000-bit block unless the counts of its 4-bit segments give 2.16 < X < 46.17.
+    pub fn fips_poker(samples: &[u8]) -> (f64, f64, f64) {
+        Onod::fips(samples, FipsTest::Poker)
+    }
+
+    /// Runs test of FIPS 140-2
+    /// Fails a 20,000-bit block unless its runs of zeros and of ones of length 1 to 5 and 6+ each fall in the
+    /// standard's intervals.
+    pub fn fips_runs(samples: &[u8]) -> (f64, f64, f64) {
+        Onod::fips(samples, FipsTest::Runs)
+    }
+
+    /// Long run test of FIPS 140-2
+    /// Fails a 20,000-bit block holding a run of 26 or more equal bits.
+    pub fn fips_long_run(samples: &[u8]) -> (f64, f64, f64) {
+        Onod::fips(samples, FipsTest::LongRun)
+    }
+
+    /// The four FIPS 140-2 tests together
+    /// Fails a 20,000-bit block that fails any of them. Returns the fraction of failed blocks and the binomial
+    /// probability of failing at least as many.
+    pub fn fips140(samples: &[u8]) -> (f64, f64, f64) {
+        Onod::fips(samples, FipsTest::All)
+    }
+
+    /// Runs one FIPS 140-2 test, or all of them, over consecutive 20,000-bit blocks of `samples`.
+    pub fn fips(samples: &[u8], test: FipsTest) -> (f64, f64, f64) {
+        let mut fips = Fips::new(test);
+        fips.update(samples);
+        fips.finish()
+    }
+
+    /// Statistics of every complete 20,000-bit block of `samples`.
+    pub fn fips_blocks(samples: &[u8]) -> Vec<FipsBlock> {
+        samples.chunks_exact(FIPS_BLOCK_BYTES).map(FipsBlock::new).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighs_failed_blocks_against_the_false_alarm_rate() {
+        let mut state = 1u32;
+        let mut samples: Vec<u8> = (0..9 * FIPS_BLOCK_BYTES)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 24) as u8
+            })
+            .collect();
+        assert!(Onod::fips_blocks(&samples).iter().all(FipsBlock::passed));
+        assert_eq!(Onod::fips140(&samples), (0.0, 0.0, 1.0));
+
+        // A single failed block is the standard's verdict, one in ten is about as likely as ten false alarm rates
+        assert_eq!(Onod::fips140(&[0; FIPS_BLOCK_BYTES]), (1.0, 0.0, 0.0));
+        samples.extend([0; FIPS_BLOCK_BYTES]);
+        for test in [FipsTest::Monobit, FipsTest::Poker, FipsTest::Runs, FipsTest::LongRun, FipsTest::All] {
+            let (failure_rate, _, p_value) = Onod::fips(&samples, test);
+            let expected = 1.0 - (1.0 - test.false_alarm_rate()).powi(10);
+            assert_eq!(failure_rate, 0.1);
+            assert!((p_value - expected).abs() < 1e-9 * expected, "{test:?}: {p_value} vs {expected}");
+        }
+
+        // Two failed blocks in ten are far beyond a false alarm
+        samples.extend([0; FIPS_BLOCK_BYTES]);
+        assert!(Onod::fips140(&samples[FIPS_BLOCK_BYTES..]).2 < 1e-3);
+    }
+}
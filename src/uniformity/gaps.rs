@@ -26,75 +26,82 @@
 use statrs::distribution::{ChiSquared, ContinuousCDF};
 
 use crate::Onod;
+use crate::stream::Accumulator;
 
-impl Onod {
+// Bin edges and expected frequencies from the Java implementation
+const BIN_EDGES: [u64; 11] = [0, 26, 56, 90, 130, 176, 233, 307, 410, 587, 1_000_000_000];
+const EXPECTED_FREQUENCIES: [f64; 10] = [
+    0.10028324483130746,
+    0.09967572210885733,
+    0.09968386797087492,
+    0.10149320063058609,
+    0.09867042665174708,
+    0.10001818308995936,
+    0.10062746966933134,
+    0.09938275862858448,
+    0.10004382368984566,
+    0.1001213027289063,
+];
 
-    /// Gaps randomness test
-    /// Analyzes the gaps between occurrences of a specific byte value and returns a p-value.
-    pub fn gaps(samples: &[u8]) -> (f64, f64, f64) {
-    
-        if samples.is_empty() {
-            return (-1.0, 0.0, 1.0); // empty data
-        }
-    
-        // Bin edges and expected frequencies from the Java implementation
-        let bin_edges = [0, 26, 56, 90, 130, 176, 233, 307, 410, 587, 1_000_000_000];
-        let expected_frequencies = [
-            0.10028324483130746,
-            0.09967572210885733,
-            0.09968386797087492,
-            0.10149320063058609,
-            0.09867042665174708,
-            0.10001818308995936,
-            0.10062746966933134,
-            0.09938275862858448,
-            0.10004382368984566,
-            0.1001213027289063,
-        ];
-    
-        let mut all_gaps = Vec::new();
-    
-        // Measure gaps for all unique values
-        for category_pointer in 0..samples.len() - 1 {
-            for i in category_pointer + 1..samples.len() {
-                if samples[i] == samples[category_pointer] {
-                    let gap = i - category_pointer - 1;
-                    all_gaps.push(gap as u32);
-                    break;
+/// Streaming state of the gaps test.
+pub struct Gaps {
+    // Position of the most recent occurrence of each byte value
+    last_seen: [Option<u64>; 256],
+    position: u64,
+    // Histogram of observed gaps
+    observed: [u64; 10],
+}
+
+impl Default for Gaps {
+    fn default() -> Self {
+        Gaps { last_seen: [None; 256], position: 0, observed: [0; 10] }
+    }
+}
+
+impl Accumulator for Gaps {
+    fn update(&mut self, chunk: &[u8]) {
+        // Measure the gap back to the previous occurrence of each value
+        for &byte in chunk {
+            if let Some(previous) = self.last_seen[byte as usize] {
+                let gap = self.position - previous - 1;
+                for i in 0..BIN_EDGES.len() - 1 {
+                    if gap <= BIN_EDGES[i + 1] {
+                        self.observed[i] += 1;
+                        break;
+                    }
                 }
             }
+            self.last_seen[byte as usize] = Some(self.position);
+            self.position += 1;
         }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
     
-        if all_gaps.is_empty() {
-            return (-1.0, 0.0, 1.0); // No gaps found
+        if self.position == 0 {
+            return (-1.0, 0.0, 1.0); // empty data
         }
     
-        // Create histogram of observed gaps
-        let mut observed = vec![0; bin_edges.len() - 1];
-        for &gap in &all_gaps {
-            for i in 0..bin_edges.len() - 1 {
-                if (gap as u32) <= bin_edges[i + 1] {
-                    observed[i] += 1;
-                    break;
-                }
-            }
+        let total_gaps: u64 = self.observed.iter().sum();
+        if total_gaps == 0 {
+            return (-1.0, 0.0, 1.0); // No gaps found
         }
     
         // Calculate expected counts for each bin
-        let total_gaps = all_gaps.len() as f64;
-        let expected: Vec<f64> = expected_frequencies
+        let total_gaps = total_gaps as f64;
+        let expected: Vec<f64> = EXPECTED_FREQUENCIES
             .iter()
             .map(|&freq| freq * total_gaps)
             .collect();
     
         // Perform chi-square test
-        let chi_squared_stat: f64 = observed
+        let chi_squared_stat: f64 = self.observed
             .iter()
             .zip(expected.iter())
             .map(|(&o, &e)| if e > 0.0 { (o as f64 - e).powi(2) / e } else { 0.0 })
             .sum();
     
-        let degrees_of_freedom = bin_edges.len() as f64 - 2.0; // Number of bins - 1
+        let degrees_of_freedom = BIN_EDGES.len() as f64 - 2.0; // Number of bins - 1
         let chi_squared_dist = ChiSquared::new(degrees_of_freedom).expect("Failed to create ChiSquared distribution");
         let p_value = 1.0 - chi_squared_dist.cdf(chi_squared_stat);
     
@@ -104,6 +111,28 @@ impl Onod {
         let z_score = (chi_squared_stat - mean) / std_dev;
 
         (chi_squared_stat, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Gaps randomness test
+    /// Analyzes the gaps between occurrences of a specific byte value and returns a p-value.
+    pub fn gaps(samples: &[u8]) -> (f64, f64, f64) {
+        let mut gaps = Gaps::default();
+        gaps.update(samples);
+        gaps.finish()
     }    
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::assert_chunking_invariant;
+
+    #[test]
+    fn chunked_input_matches_one_shot() {
+        assert_chunking_invariant(Gaps::default);
+    }
+}
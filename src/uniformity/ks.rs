@@ -23,72 +23,92 @@
 // SOFTWARE.
 
 
-use kolmogorov_smirnov::test_f64;
 use std::time::{SystemTime, UNIX_EPOCH};
 use statrs::distribution::ContinuousCDF;
 
 use crate::Onod;
+use crate::stream::Accumulator;
 use crate::well19937c::Well19937c;
 
-impl Onod {
-    /// KS randomness test
-    /// Performs the Kolmogorov-Smirnov test to evaluate the uniformity of data distribution
-    /// and returns the test statistic (D-statistic), z-score, and p-value.
-    pub fn ks(samples: &[u8]) -> (f64, f64, f64) {
-        if samples.is_empty() {
-            return (-1.0, 0.0, 1.0); // Invalid input
+/// Streaming state of the KS test.
+/// Both samples only take 256 distinct positions on the ECDF, so histograms replace the sorted copies.
+pub struct Ks {
+    counts: [u64; 256],
+    seed: Option<u32>,
+}
+
+impl Default for Ks {
+    fn default() -> Self {
+        Ks { counts: [0; 256], seed: None }
+    }
+}
+
+impl Ks {
+    /// Uses a fixed seed for the reference distribution instead of the current time, for reproducible results.
+    pub fn with_seed(seed: u32) -> Self {
+        Ks { counts: [0; 256], seed: Some(seed) }
+    }
+}
+
+impl Accumulator for Ks {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            self.counts[byte as usize] += 1;
         }
-    
-        // Normalize the input samples to [0, 1) range
-        let mut normalized_samples: Vec<f64> = samples.iter().map(|&x| x as f64 / 255.0).collect();
-    
-        // Generate a uniform distribution for comparison
-        let mut uniform_distribution: Vec<f64> = Self::generate_uniform_distribution(normalized_samples.len(), Self::get_timestamp_seed());
-    
-        // Sort both distributions
-        normalized_samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        uniform_distribution.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    
-        let debug = false;
-        // Debugging: Optional print sorted values
-        if debug {
-            println!("Sorted Normalized Samples: {:?}", normalized_samples);
-            println!("Sorted Uniform Distribution: {:?}", uniform_distribution);
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+        let len: u64 = self.counts.iter().sum();
+        if len == 0 {
+            return (-1.0, 0.0, 1.0); // Invalid input
         }
-    
-        // Perform the Kolmogorov-Smirnov test
-        let confidence = 0.05; // Significance level
-        let result = test_f64(&normalized_samples, &uniform_distribution, confidence);
-    
-        // Extract the KS statistic (D-statistic)
-        let ks_statistic = result.statistic;
-    
-        // Debugging: Print ECDF differences and max difference (D-statistic)
-        if debug {
-            for (i, (&sample, &uniform)) in normalized_samples.iter().zip(&uniform_distribution).enumerate() {
-                let diff = (sample - uniform).abs();
-                println!(
-                    "Index: {}, Sample: {:.6}, Uniform: {:.6}, Difference: {:.6}",
-                    i, sample, uniform, diff
-                );
-            }
-            println!("D-Statistic: {:.6}", ks_statistic);
+
+        // Bin a uniform distribution of the same size into the intervals [k/255, (k+1)/255)
+        // that separate the normalized samples
+        let uniform_counts = Onod::generate_uniform_histogram(len, self.seed.unwrap_or_else(Onod::get_timestamp_seed));
+
+        // Two-sample Kolmogorov-Smirnov statistic (D-statistic). Between two sample positions the
+        // ECDF of the normalized samples is flat, so the largest difference is found at either end.
+        let n = len as f64;
+        let mut ecdf_samples = 0.0;
+        let mut ecdf_uniform = 0.0;
+        let mut ks_statistic: f64 = 0.0;
+        for (&count, &uniform_count) in self.counts.iter().zip(uniform_counts.iter()) {
+            ecdf_samples += count as f64 / n;
+            ks_statistic = ks_statistic.max((ecdf_samples - ecdf_uniform).abs());
+            ecdf_uniform += uniform_count as f64 / n;
+            ks_statistic = ks_statistic.max((ecdf_samples - ecdf_uniform).abs());
         }
     
         // Calculate the z-score
-        let sample_size = normalized_samples.len() as f64;
-        let z_score = ks_statistic * sample_size.sqrt();
+        let z_score = ks_statistic * n.sqrt();
     
         // Calculate the p-value
         let p_value = 2.0 * (1.0 - statrs::distribution::Normal::new(0.0, 1.0).unwrap().cdf(z_score.abs()));
     
         (ks_statistic, z_score, p_value)
     }
+}
 
-    /// Generates a uniform distribution of the same length as the input data.
-    fn generate_uniform_distribution(len: usize, seed: u32) -> Vec<f64> {
+impl Onod {
+    /// KS randomness test
+    /// Performs the Kolmogorov-Smirnov test to evaluate the uniformity of data distribution
+    /// and returns the test statistic (D-statistic), z-score, and p-value.
+    pub fn ks(samples: &[u8]) -> (f64, f64, f64) {
+        let mut ks = Ks::default();
+        ks.update(samples);
+        ks.finish()
+    }
+
+    /// Generates a uniform distribution of `len` values and counts them per normalized byte interval.
+    fn generate_uniform_histogram(len: u64, seed: u32) -> [u64; 256] {
         let mut rng = Well19937c::new(seed);
-        (0..len).map(|_| rng.next_f64()).collect()
+        let mut counts = [0u64; 256];
+        for _ in 0..len {
+            let bin = (rng.next_f64() * 255.0).floor() as usize;
+            counts[bin.min(255)] += 1;
+        }
+        counts
     }
 
     fn get_timestamp_seed() -> u32 {
@@ -98,4 +118,15 @@ impl Onod {
         // Use seconds or nanoseconds as the seed
         (duration.as_secs() as u32) ^ (duration.subsec_nanos())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::assert_chunking_invariant;
+
+    #[test]
+    fn chunked_input_matches_one_shot() {
+        assert_chunking_invariant(|| Ks::with_seed(1));
+    }
+}
@@ -0,0 +1,315 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::function::gamma::gamma_ur;
+
+use crate::Onod;
+use crate::stream::Accumulator;
+
+/// Block size M used when none is given, the lower end of NIST's recommended 500 to 5000.
+pub const LINEAR_COMPLEXITY_BLOCK_SIZE: usize = 500;
+
+/// Probabilities of the classes T <= -2.5, (-2.5, -1.5], ... (1.5, 2.5], T > 2.5.
+const CLASSES: [f64; 7] = [1.0 / 96.0, 1.0 / 32.0, 1.0 / 8.0, 1.0 / 2.0, 1.0 / 4.0, 1.0 / 16.0, 1.0 / 48.0];
+
+/// Berlekamp-Massey algorithm over GF(2), fed one bit at a time.
+///
+/// Tracks the shortest linear feedback shift register generating the bits seen so far. Polynomials
+/// are bit sets with coefficient i at bit i, and the sequence is stored back to front so the
+/// discrepancy is a word-wise product of the connection polynomial with a slice of it.
+#[derive(Clone, Debug, Default)]
+pub struct BerlekampMassey {
+    bits: usize,
+    // Bit s_j of the sequence at index capacity - 1 - j
+    reversed: Vec<u64>,
+    connection: Vec<u64>,
+    previous: Vec<u64>,
+    spare: Vec<u64>,
+    complexity: usize,
+    shift: usize,
+}
+
+impl BerlekampMassey {
+    pub fn new() -> Self {
+        BerlekampMassey::with_capacity(0)
+    }
+
+    /// Preallocates room for `bits` bits.
+    pub fn with_capacity(bits: usize) -> Self {
+        let mut berlekamp_massey = BerlekampMassey { reversed: vec![0; bits.div_ceil(64).max(1)], ..Default::default() };
+        berlekamp_massey.reset();
+        berlekamp_massey
+    }
+
+    /// Forgets every bit, keeping the allocated memory.
+    pub fn reset(&mut self) {
+        self.bits = 0;
+        self.reversed.iter_mut().for_each(|word| *word = 0);
+        self.connection.clear();
+        self.connection.push(1);
+        self.previous.clear();
+        self.previous.push(1);
+        self.complexity = 0;
+        self.shift = 1;
+    }
+
+    /// Linear complexity of the bits seen so far.
+    pub fn linear_complexity(&self) -> usize {
+        self.complexity
+    }
+
+    /// Feeds the next bit and returns the linear complexity including it.
+    pub fn push_bit(&mut self, bit: bool) -> usize {
+        let n = self.bits;
+        if n == 64 * self.reversed.len() {
+            // Double the capacity, moving the sequence to the top
+            let words = self.reversed.len();
+            self.reversed.splice(0..0, std::iter::repeat_n(0, words));
+        }
+        let capacity = 64 * self.reversed.len();
+        let position = capacity - 1 - n;
+        self.reversed[position / 64] |= (bit as u64) << (position % 64);
+        self.bits += 1;
+
+        // Discrepancy: s_n + sum of c_i * s_(n-i) for i = 1..L
+        let mut discrepancy = 0;
+        for (w, &coefficients) in self.connection.iter().enumerate().take(self.complexity / 64 + 1) {
+            discrepancy ^= coefficients & bits_at(&self.reversed, position + 64 * w);
+        }
+        if discrepancy.count_ones() % 2 == 0 {
+            self.shift += 1;
+            return self.complexity;
+        }
+
+        let lengthen = 2 * self.complexity <= n;
+        if lengthen {
+            self.spare.clone_from(&self.connection);
+        }
+
+        // C(x) += x^shift * B(x)
+        let (words, bits) = (self.shift / 64, self.shift % 64);
+        let length = self.previous.len() + words + 1;
+        if self.connection.len() < length {
+            self.connection.resize(length, 0);
+        }
+        for (w, &coefficients) in self.previous.iter().enumerate() {
+            self.connection[w + words] ^= coefficients << bits;
+            if bits > 0 {
+                self.connection[w + words + 1] ^= coefficients >> (64 - bits);
+            }
+        }
+        while self.connection.len() > 1 && self.connection.last() == Some(&0) {
+            self.connection.pop();
+        }
+
+        if lengthen {
+            self.complexity = n + 1 - self.complexity;
+            std::mem::swap(&mut self.previous, &mut self.spare);
+            self.shift = 1;
+        } else {
+            self.shift += 1;
+        }
+        self.complexity
+    }
+}
+
+/// The 64 bits of `words` starting at bit `start`, zero past the end.
+fn bits_at(words: &[u64], start: usize) -> u64 {
+    let (w, b) = (start / 64, start % 64);
+    let low = words.get(w).map_or(0, |&word| word >> b);
+    match b {
+        0 => low,
+        _ => low | words.get(w + 1).map_or(0, |&word| word << (64 - b)),
+    }
+}
+
+/// Streaming state of the NIST SP 800-22 linear complexity test.
+pub struct LinearComplexity {
+    block_size: usize,
+    berlekamp_massey: BerlekampMassey,
+    observed: [u64; 7],
+}
+
+impl Default for LinearComplexity {
+    fn default() -> Self {
+        LinearComplexity::new(LINEAR_COMPLEXITY_BLOCK_SIZE)
+    }
+}
+
+impl LinearComplexity {
+    /// Measures the linear complexity of blocks of `block_size` bits, the NIST parameter M.
+    pub fn new(block_size: usize) -> Self {
+        LinearComplexity { block_size, berlekamp_massey: BerlekampMassey::with_capacity(block_size), observed: [0; 7] }
+    }
+}
+
+impl Accumulator for LinearComplexity {
+    fn update(&mut self, chunk: &[u8]) {
+        let m = self.block_size as f64;
+        let sign = if self.block_size.is_multiple_of(2) { 1.0 } else { -1.0 };
+        // Mean linear complexity of a random block of M bits
+        let mean = m / 2.0 + (9.0 - sign) / 36.0 - (m / 3.0 + 2.0 / 9.0) / 2f64.powf(m);
+
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                let complexity = self.berlekamp_massey.push_bit((byte >> shift) & 1 == 1);
+                if self.berlekamp_massey.bits < self.block_size {
+                    continue;
+                }
+
+                let t = sign * (complexity as f64 - mean) + 2.0 / 9.0;
+                let class = match t {
+                    t if t <= -2.5 => 0,
+                    t if t > 2.5 => 6,
+                    t => (t + 3.5).ceil() as usize - 1,
+                };
+                self.observed[class] += 1;
+                self.berlekamp_massey.reset();
+            }
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        let n = self.observed.iter().sum::<u64>() as f64;
+        if n == 0.0 {
+            return (-1.0, 0.0, 1.0); // Not a single complete block
+        }
+
+        let chi_square: f64 = self.observed
+            .iter()
+            .zip(CLASSES.iter())
+            .map(|(&observed, &probability)| (observed as f64 - n * probability).powi(2) / (n * probability))
+            .sum();
+
+        // chi^2 has 6 degrees of freedom
+        let z_score = (chi_square - 6.0) / 12f64.sqrt();
+        let p_value = gamma_ur(3.0, chi_square / 2.0);
+
+        (chi_square, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Linear complexity test (NIST SP 800-22, 2.10)
+    /// Compares the lengths of the shortest LFSRs generating blocks of M bits to those of random blocks and returns a p-value.
+    pub fn linear_complexity(samples: &[u8]) -> (f64, f64, f64) {
+        let mut linear_complexity = LinearComplexity::default();
+        linear_complexity.update(samples);
+        linear_complexity.finish()
+    }
+
+    /// Linear complexity profile of the whole bit sequence: entry k is the linear complexity of the first k + 1 bits.
+    /// Random data follows k / 2 closely, while data from an LFSR levels off at the register length.
+    /// Takes time quadratic in the number of bits.
+    pub fn linear_complexity_profile(samples: &[u8]) -> Vec<usize> {
+        let mut berlekamp_massey = BerlekampMassey::with_capacity(8 * samples.len());
+        samples
+            .iter()
+            .flat_map(|&byte| (0..8).rev().map(move |shift| (byte >> shift) & 1 == 1))
+            .map(|bit| berlekamp_massey.push_bit(bit))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::{xorshift_bytes, E};
+
+    /// Linear complexity of `bits` by definition: the shortest L for which s_j = c_1 s_(j-1) + ... + c_L s_(j-L)
+    /// holds for all j >= L, found by Gaussian elimination over GF(2). Takes the previous prefix's L as a lower bound
+    /// and at most 128 bits.
+    fn naive_linear_complexity(bits: &[bool], lower_bound: usize) -> usize {
+        (lower_bound..bits.len())
+            .find(|&l| {
+                let mut rows: Vec<(u128, bool)> = (l..bits.len())
+                    .map(|j| ((1..=l).fold(0, |mask, i| mask | (bits[j - i] as u128) << (i - 1)), bits[j]))
+                    .collect();
+                for column in 0..l {
+                    let Some(pivot) = rows.iter().position(|&(mask, _)| mask >> column & 1 == 1) else {
+                        continue;
+                    };
+                    let pivot = rows.swap_remove(pivot);
+                    for row in rows.iter_mut().filter(|(mask, _)| mask >> column & 1 == 1) {
+                        *row = (row.0 ^ pivot.0, row.1 ^ pivot.1);
+                    }
+                }
+                rows.iter().all(|&(_, rhs)| !rhs)
+            })
+            .unwrap_or(bits.len())
+    }
+
+    fn naive_profile(bits: &[bool]) -> Vec<usize> {
+        let mut profile: Vec<usize> = Vec::with_capacity(bits.len());
+        for n in 1..=bits.len() {
+            profile.push(naive_linear_complexity(&bits[..n], profile.last().copied().unwrap_or(0)));
+        }
+        profile
+    }
+
+    #[test]
+    fn berlekamp_massey_matches_definition() {
+        // Example of section 2.10.4
+        let epsilon: Vec<bool> = "1101011110001".chars().map(|bit| bit == '1').collect();
+        let mut berlekamp_massey = BerlekampMassey::new();
+        epsilon.iter().for_each(|&bit| _ = berlekamp_massey.push_bit(bit));
+        assert_eq!(berlekamp_massey.linear_complexity(), 4);
+        assert_eq!(naive_linear_complexity(&epsilon, 0), 4);
+
+        // Every sequence of 12 bits
+        for sequence in 0u32..1 << 12 {
+            let bits: Vec<bool> = (0..12).rev().map(|i| sequence >> i & 1 == 1).collect();
+            let mut berlekamp_massey = BerlekampMassey::new();
+            let profile: Vec<usize> = bits.iter().map(|&bit| berlekamp_massey.push_bit(bit)).collect();
+            assert_eq!(profile, naive_profile(&bits), "{:012b}", sequence);
+        }
+
+        // Longer sequences, whose bits and connection polynomials span two words
+        let samples = xorshift_bytes(8 * 16);
+        for sequence in samples.chunks(16) {
+            let bits: Vec<bool> =
+                sequence.iter().flat_map(|&byte| (0..8).rev().map(move |i| byte >> i & 1 == 1)).collect();
+            assert_eq!(Onod::linear_complexity_profile(sequence), naive_profile(&bits));
+        }
+    }
+
+    #[test]
+    fn matches_nist_examples() {
+        // Section 2.10.8: 1000 blocks of M = 1000 bits of e. NIST rounds pi_0 = 1/96 to 0.01047 and prints
+        // chi^2 = 2.700348 and P = 0.845406, the exact probabilities give chi^2 = 2.706.
+        let mut linear_complexity = LinearComplexity::new(1000);
+        linear_complexity.update(E);
+        assert_eq!(linear_complexity.observed, [11, 31, 116, 501, 258, 57, 26]);
+        let (chi_square, _, p_value) = linear_complexity.finish();
+        assert!((chi_square - 2.706).abs() < 1e-9, "chi^2 = {}", chi_square);
+        assert!((p_value - 0.844738).abs() < 1e-6, "p = {}", p_value);
+
+        // Appendix B: M = 500
+        let p_value = Onod::linear_complexity(E).2;
+        assert!((p_value - 0.826202).abs() < 1e-6, "p = {}", p_value);
+    }
+}
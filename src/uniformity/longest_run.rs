@@ -0,0 +1,202 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::function::gamma::gamma_ur;
+
+use crate::Onod;
+use crate::stream::Accumulator;
+
+/// Block sizes M of NIST SP 800-22 with the shortest input each is recommended for,
+/// the class boundaries of the longest run and the class probabilities.
+const CATEGORIES: [(usize, u64, u32, &[f64]); 3] = [
+    (8, 128, 1, &[0.21484375, 0.3671875, 0.23046875, 0.1875]),
+    (128, 6272, 4, &[0.1174, 0.2430, 0.2493, 0.1752, 0.1027, 0.1124]),
+    (10_000, 750_000, 10, &[0.0882, 0.2092, 0.2483, 0.1933, 0.1208, 0.0675, 0.0727]),
+];
+
+/// Longest runs of ones in blocks of one size.
+struct Blocks {
+    block_size: usize,
+    bits: usize,
+    run: u32,
+    longest: u32,
+    // Number of blocks per class, the first class holds all runs up to `shortest`
+    shortest: u32,
+    classes: Vec<u64>,
+}
+
+impl Blocks {
+    fn new(block_size: usize, shortest: u32, classes: usize) -> Self {
+        Blocks { block_size, bits: 0, run: 0, longest: 0, shortest, classes: vec![0; classes] }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        self.run = if bit { self.run + 1 } else { 0 };
+        self.longest = self.longest.max(self.run);
+        self.bits += 1;
+        if self.bits == self.block_size {
+            let class = (self.longest.saturating_sub(self.shortest) as usize).min(self.classes.len() - 1);
+            self.classes[class] += 1;
+            self.bits = 0;
+            self.run = 0;
+            self.longest = 0;
+        }
+    }
+}
+
+/// Streaming state of the NIST SP 800-22 longest run of ones in a block test.
+///
+/// The block size is picked from the input length when the test completes unless
+/// one was given, so all three block sizes are tracked side by side.
+pub struct LongestRun {
+    block_size: Option<usize>,
+    bits: u64,
+    blocks: Vec<Blocks>,
+}
+
+impl Default for LongestRun {
+    fn default() -> Self {
+        LongestRun::new(None)
+    }
+}
+
+impl LongestRun {
+    /// Uses blocks of `block_size` bits (8, 128 or 10000), or picks one from the input length if `None`.
+    pub fn new(block_size: Option<usize>) -> Self {
+        let blocks = CATEGORIES
+            .iter()
+            .filter(|(m, ..)| block_size.is_none_or(|size| size == *m))
+            .map(|&(m, _, shortest, probabilities)| Blocks::new(m, shortest, probabilities.len()))
+            .collect();
+        LongestRun { block_size, bits: 0, blocks }
+    }
+
+    /// Block sizes the test is defined for.
+    pub fn block_sizes() -> impl Iterator<Item = usize> {
+        CATEGORIES.iter().map(|(m, ..)| *m)
+    }
+}
+
+impl Accumulator for LongestRun {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                let bit = (byte >> shift) & 1 == 1;
+                for blocks in self.blocks.iter_mut() {
+                    blocks.push_bit(bit);
+                }
+            }
+        }
+        self.bits += 8 * chunk.len() as u64;
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        // Largest block size the input is long enough for
+        let block_size = match self.block_size {
+            Some(block_size) => block_size,
+            None => match CATEGORIES.iter().rev().find(|(_, shortest_input, ..)| self.bits >= *shortest_input) {
+                Some(&(m, ..)) => m,
+                None => return (-1.0, 0.0, 1.0), // Fewer than 128 bits
+            },
+        };
+
+        let (_, _, _, probabilities) = CATEGORIES.iter().find(|(m, ..)| *m == block_size).unwrap();
+        let blocks = self.blocks.iter().find(|blocks| blocks.block_size == block_size).unwrap();
+        let n: u64 = blocks.classes.iter().sum();
+        if n == 0 {
+            return (-1.0, 0.0, 1.0); // Not a single complete block
+        }
+
+        let chi_square: f64 = blocks.classes
+            .iter()
+            .zip(probabilities.iter())
+            .map(|(&observed, &probability)| {
+                let expected = n as f64 * probability;
+                (observed as f64 - expected).powi(2) / expected
+            })
+            .sum();
+
+        // chi^2 has K = classes - 1 degrees of freedom
+        let k = (probabilities.len() - 1) as f64;
+        let z_score = (chi_square - k) / (2.0 * k).sqrt();
+        let p_value = gamma_ur(k / 2.0, chi_square / 2.0);
+
+        (chi_square, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Longest run of ones in a block test (NIST SP 800-22, 2.4)
+    /// Compares the distribution of the longest run of 1s within blocks to that of random data and returns a p-value.
+    pub fn longest_run(samples: &[u8]) -> (f64, f64, f64) {
+        let mut longest_run = LongestRun::default();
+        longest_run.update(samples);
+        longest_run.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn longest_run(samples: &[u8], block_size: Option<usize>) -> (f64, f64, f64) {
+        let mut longest_run = LongestRun::new(block_size);
+        longest_run.update(samples);
+        longest_run.finish()
+    }
+
+    #[test]
+    fn matches_nist_example() {
+        // Example of SP 800-22 section 2.4.8
+        let epsilon = "11001100000101010110110001001100111000000000001001001101010100010001001111010110100000001101011111001100111001101101100010110010";
+        let samples: Vec<u8> = epsilon
+            .as_bytes()
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |acc, &bit| acc << 1 | (bit == b'1') as u8))
+            .collect();
+        let (chi_square, _, p_value) = longest_run(&samples, Some(8));
+        // The example prints chi^2 = 4.882605, computed with the class probabilities rounded to 4 digits; the exact
+        // probabilities give 4.882457, and the P-value of 0.180609 it prints
+        assert!((chi_square - 4.882605).abs() < 2e-4, "chi^2 = {}", chi_square);
+        assert!((chi_square - 4.882457).abs() < 1e-6, "chi^2 = {}", chi_square);
+        assert!((p_value - 0.180609).abs() < 1e-6, "p = {}", p_value);
+        assert_eq!(longest_run(&samples, None), longest_run(&samples, Some(8)));
+    }
+
+    #[test]
+    fn picks_block_size_from_length() {
+        // Deterministic filler with varied runs
+        let samples: Vec<u8> = (0..93_750u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect();
+        assert_eq!(longest_run(&samples[..15], None), (-1.0, 0.0, 1.0));
+        // Input lengths in bits around the 128, 6272 and 750000 bit boundaries
+        for (bytes, block_size, other) in [(16, 8, 128), (783, 8, 128), (784, 128, 8), (93_749, 128, 10_000), (93_750, 10_000, 128)] {
+            let picked = longest_run(&samples[..bytes], None);
+            assert_eq!(picked, longest_run(&samples[..bytes], Some(block_size)), "{} bits", 8 * bytes);
+            assert_ne!(picked, longest_run(&samples[..bytes], Some(other)), "{} bits", 8 * bytes);
+        }
+    }
+}
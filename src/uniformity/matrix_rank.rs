@@ -0,0 +1,174 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use crate::Onod;
+use crate::chisquaretest::{chi_square_statistic, chi_square_test};
+use crate::stream::Accumulator;
+
+/// Matrix sizes of NIST SP 800-22 (32x32) and Diehard (31x31, 6x8).
+pub const NIST_MATRIX: (usize, usize) = (32, 32);
+pub const DIEHARD_MATRIX: (usize, usize) = (31, 31);
+pub const DIEHARD_SMALL_MATRIX: (usize, usize) = (6, 8);
+
+/// Streaming state of the binary matrix rank test.
+///
+/// Consecutive bits fill the matrices row by row.
+pub struct MatrixRank {
+    rows: usize,
+    cols: usize,
+    matrix: Vec<u64>,
+    row: usize,
+    col: usize,
+    // Matrices of full rank, of rank one less, and of lower rank
+    observed: [u64; 3],
+}
+
+impl Default for MatrixRank {
+    fn default() -> Self {
+        MatrixRank::new(NIST_MATRIX.0, NIST_MATRIX.1)
+    }
+}
+
+impl MatrixRank {
+    /// Ranks matrices of `rows` x `cols` bits, both between 2 and 64.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        assert!((2..=64).contains(&rows) && (2..=64).contains(&cols), "Matrix dimensions must be between 2 and 64.");
+        MatrixRank { rows, cols, matrix: vec![0; rows], row: 0, col: 0, observed: [0; 3] }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        self.matrix[self.row] = (self.matrix[self.row] << 1) | bit as u64;
+        self.col += 1;
+        if self.col < self.cols {
+            return;
+        }
+        self.col = 0;
+        self.row += 1;
+        if self.row < self.rows {
+            return;
+        }
+        self.row = 0;
+
+        let deficiency = self.rows.min(self.cols) - rank(&mut self.matrix);
+        self.observed[deficiency.min(2)] += 1;
+        self.matrix.iter_mut().for_each(|row| *row = 0);
+    }
+}
+
+/// Rank over GF(2) of a matrix whose rows are bit masks, by Gaussian elimination.
+/// The rows are reduced in place.
+pub fn rank(rows: &mut [u64]) -> usize {
+    let mut rank = 0;
+    for bit in (0..64).rev() {
+        let mask = 1u64 << bit;
+        let Some(pivot) = (rank..rows.len()).find(|&i| rows[i] & mask != 0) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let pivot_row = rows[rank];
+        for row in rows[rank + 1..].iter_mut() {
+            if *row & mask != 0 {
+                *row ^= pivot_row;
+            }
+        }
+        rank += 1;
+        if rank == rows.len() {
+            break;
+        }
+    }
+    rank
+}
+
+/// Probability that a random `rows` x `cols` matrix over GF(2) has rank `r`.
+pub fn rank_probability(rows: usize, cols: usize, r: usize) -> f64 {
+    let (m, q) = (rows as i32, cols as i32);
+    let r = r as i32;
+    let mut product = 1.0;
+    for i in 0..r {
+        product *= (1.0 - 2f64.powi(i - q)) * (1.0 - 2f64.powi(i - m)) / (1.0 - 2f64.powi(i - r));
+    }
+    2f64.powi(r * (q + m - r) - m * q) * product
+}
+
+impl Accumulator for MatrixRank {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                self.push_bit((byte >> shift) & 1 == 1);
+            }
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if self.observed.iter().sum::<u64>() == 0 {
+            return (-1.0, 0.0, 1.0); // Not a single complete matrix
+        }
+
+        let full_rank = self.rows.min(self.cols);
+        let p_full = rank_probability(self.rows, self.cols, full_rank);
+        let p_deficient = rank_probability(self.rows, self.cols, full_rank - 1);
+        let expected = [p_full, p_deficient, 1.0 - p_full - p_deficient];
+
+        // Three categories, two degrees of freedom
+        let chi_square = chi_square_statistic(&self.observed, &expected);
+        let z_score = (chi_square - 2.0) / 2.0;
+        let p_value = chi_square_test(&self.observed, &expected);
+
+        (chi_square, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Binary matrix rank test (NIST SP 800-22, 2.5 and Diehard)
+    /// Compares the ranks over GF(2) of `rows` x `cols` bit matrices to those of random matrices and returns a p-value.
+    pub fn matrix_rank(samples: &[u8], rows: usize, cols: usize) -> (f64, f64, f64) {
+        let mut matrix_rank = MatrixRank::new(rows, cols);
+        matrix_rank.update(samples);
+        matrix_rank.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::E;
+
+    #[test]
+    fn matches_nist_examples() {
+        // Section 2.5.8: the first 100,000 bits of e hold 97 matrices of 32x32 bits
+        let mut matrix_rank = MatrixRank::default();
+        matrix_rank.update(&E[..12_500]);
+        assert_eq!(matrix_rank.observed, [23, 60, 14]);
+        let (chi_square, _, p_value) = matrix_rank.finish();
+        assert!((chi_square - 1.2619656).abs() < 1e-7, "chi^2 = {}", chi_square);
+        assert!((p_value - 0.532069).abs() < 1e-6, "p = {}", p_value);
+
+        // Appendix B: all 1,000,000 bits of e
+        let p_value = Onod::matrix_rank(E, 32, 32).2;
+        assert!((p_value - 0.306156).abs() < 1e-6, "p = {}", p_value);
+    }
+}
@@ -1,15 +1,27 @@
 pub mod avalanche;
+pub mod block_frequency;
 pub mod chi_bit;
 pub mod chi_byte;
 pub mod compression;
+pub mod cumulative_sums;
+pub mod dft;
+pub mod fips;
 pub mod gaps;
 pub mod ks;
+pub mod linear_complexity;
+pub mod longest_run;
+pub mod matrix_rank;
 pub mod mean_byte;
 pub mod monobit;
+pub mod nist_runs;
 pub mod pi;
 pub mod prediction;
+pub mod random_excursions;
 pub mod runs;
 pub mod runups;
+pub mod serial;
 pub mod shannon;
 pub mod shells;
-pub mod uncorrelation;
\ No newline at end of file
+pub mod template_matching;
+pub mod uncorrelation;
+pub mod universal;
\ No newline at end of file
@@ -0,0 +1,133 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::distribution::{Normal, ContinuousCDF};
+
+use crate::Onod;
+use crate::stream::Accumulator;
+
+/// Streaming state of the NIST SP 800-22 runs test on bits.
+///
+/// Unlike the ent3000 runs test, which dichotomizes bytes around their median, this
+/// counts the runs of identical bits.
+#[derive(Default)]
+pub struct NistRuns {
+    bits: u64,
+    ones: u64,
+    transitions: u64,
+    last: Option<bool>,
+}
+
+impl Accumulator for NistRuns {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            // Bit changes inside the byte and at the boundary to the previous one
+            self.transitions += ((byte ^ (byte >> 1)) & 0x7f).count_ones() as u64;
+            if let Some(last) = self.last {
+                self.transitions += (last != (byte >> 7 == 1)) as u64;
+            }
+            self.last = Some(byte & 1 == 1);
+            self.ones += byte.count_ones() as u64;
+        }
+        self.bits += 8 * chunk.len() as u64;
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if self.bits == 0 {
+            return (-1.0, 0.0, 1.0); // Default to perfect randomness for empty data
+        }
+
+        let n = self.bits as f64;
+        let pi = self.ones as f64 / n;
+        let runs = (self.transitions + 1) as f64;
+        let expected = 2.0 * n * pi * (1.0 - pi);
+        let z_score = (runs - expected) / (2.0 * n.sqrt() * pi * (1.0 - pi));
+
+        // Frequency prerequisite: with a proportion of ones this far from 1/2 the test fails without being run
+        if (pi - 0.5).abs() >= 2.0 / n.sqrt() {
+            return (runs, z_score, 0.0);
+        }
+
+        let normal_dist = Normal::new(0.0, 1.0).expect("Failed to create Normal distribution");
+        let p_value = 2.0 * (1.0 - normal_dist.cdf(z_score.abs()));
+
+        (runs, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Runs test (NIST SP 800-22, 2.3)
+    /// Compares the number of runs of identical bits with that of random data and returns a p-value.
+    /// Fails with a p-value of 0 if the proportion of 1s is too far from 1/2, as NIST prescribes.
+    pub fn nist_runs(samples: &[u8]) -> (f64, f64, f64) {
+        let mut nist_runs = NistRuns::default();
+        nist_runs.update(samples);
+        nist_runs.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::E;
+
+    /// State after the bits of `epsilon`, which need not fill whole bytes.
+    fn nist_runs(epsilon: &str) -> NistRuns {
+        let bits: Vec<bool> = epsilon.chars().map(|bit| bit == '1').collect();
+        NistRuns {
+            bits: bits.len() as u64,
+            ones: bits.iter().filter(|&&bit| bit).count() as u64,
+            transitions: bits.windows(2).filter(|pair| pair[0] != pair[1]).count() as u64,
+            last: bits.last().copied(),
+        }
+    }
+
+    #[test]
+    fn matches_nist_examples() {
+        // Section 2.3.4
+        let (runs, _, p_value) = nist_runs("1001101011").finish();
+        assert_eq!(runs, 7.0);
+        assert!((p_value - 0.147232).abs() < 1e-6, "p = {}", p_value);
+
+        // Section 2.3.8: pi = 0.42
+        let epsilon = "1100100100001111110110101010001000100001011010001100001000110100\
+                       110001001100011001100010100010111000";
+        let (runs, _, p_value) = nist_runs(epsilon).finish();
+        assert_eq!(runs, 52.0);
+        assert!((p_value - 0.500798).abs() < 1e-6, "p = {}", p_value);
+
+        // The state from whole bytes agrees with the one the examples are counted into
+        let mut streamed = NistRuns::default();
+        streamed.update(&E[..12]);
+        let prefix: String = E[..12].iter().map(|byte| format!("{:08b}", byte)).collect();
+        assert_eq!(streamed.finish(), nist_runs(&prefix).finish());
+
+        // Appendix B: the 1,000,000 bits of e
+        let p_value = Onod::nist_runs(E).2;
+        assert!((p_value - 0.561917).abs() < 1e-6, "p = {}", p_value);
+    }
+}
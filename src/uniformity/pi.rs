@@ -23,35 +23,45 @@
 // SOFTWARE.
 
 
-/// Pi randomness test
-/// Uses a Monte Carlo simulation to estimate randomness by calculating the approximation of Pi.
+// Pi randomness test
+// Uses a Monte Carlo simulation to estimate randomness by calculating the approximation of Pi.
 
 use statrs::distribution::{Normal, ContinuousCDF};
 
 use crate::Onod;
+use crate::stream::{Accumulator, Blocks};
 
-impl Onod {
-    /// Pi randomness test using nalgebra for vectorized operations.
-    pub fn pi(samples: &[u8]) -> (f64, f64, f64) {
-        if samples.len() < 4 {
-            return (-1.0, 0.0, 1.0); // Not enough data
-        }
-    
-        let normalized_samples: Vec<f32> = get_floats(samples);
-    
-        if normalized_samples.is_empty() {
-            return (-1.0, 0.0, 1.0);
-        }
-    
-        let mut sum_y = 0.0;
-        let count = normalized_samples.len() as f64;
-    
-        for &x in &normalized_samples {
+/// Streaming state of the Pi test.
+pub struct Pi {
+    floats: Blocks,
+    sum_y: f64,
+    count: u64,
+}
+
+impl Default for Pi {
+    fn default() -> Self {
+        Pi { floats: Blocks::new(4), sum_y: 0.0, count: 0 }
+    }
+}
+
+impl Accumulator for Pi {
+    fn update(&mut self, chunk: &[u8]) {
+        let (sum_y, count) = (&mut self.sum_y, &mut self.count);
+        self.floats.feed(chunk, |float| {
+            let x = get_float(float);
             let y = (1.0 - x.powi(2)).sqrt();
-            sum_y += y as f64;
+            *sum_y += y as f64;
+            *count += 1;
+        });
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+        if self.count == 0 {
+            return (-1.0, 0.0, 1.0); // Not enough data
         }
     
-        let mean_y = sum_y / count;
+        let count = self.count as f64;
+        let mean_y = self.sum_y / count;
         let test_statistic = 4.0 * mean_y;
         let variance = compute_variance(count);
         let std_dev = variance.sqrt();
@@ -62,18 +72,22 @@ impl Onod {
     
         (test_statistic, z_score, p_value)
     }
-    
 }
 
-fn get_floats(samples: &[u8]) -> Vec<f32> {
-    let mut floats = Vec::new();
-    for chunk in samples.chunks_exact(4) {
-        let int_val = i32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-        let unsigned_val = (int_val as u32) >> 1; // Discard sign bit
-        let normalized = unsigned_val as f32 / i32::MAX as f32;
-        floats.push(normalized);
+impl Onod {
+    /// Pi randomness test using nalgebra for vectorized operations.
+    pub fn pi(samples: &[u8]) -> (f64, f64, f64) {
+        let mut pi = Pi::default();
+        pi.update(samples);
+        pi.finish()
     }
-    floats
+    
+}
+
+fn get_float(chunk: &[u8]) -> f32 {
+    let int_val = i32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    let unsigned_val = (int_val as u32) >> 1; // Discard sign bit
+    unsigned_val as f32 / i32::MAX as f32
 }
 
 fn compute_variance(n: f64) -> f64 {
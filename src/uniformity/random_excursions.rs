@@ -0,0 +1,285 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::distribution::{Normal, ContinuousCDF};
+use statrs::function::gamma::gamma_ur;
+
+use crate::Onod;
+use crate::stream::{Accumulator, TestResult};
+
+/// Largest |x| of the states of the random excursions test and of its variant.
+pub const EXCURSION_STATES: i64 = 4;
+pub const VARIANT_STATES: i64 = 9;
+
+/// Fewest cycles for which NIST SP 800-22 applies the tests.
+const MIN_CYCLES: u64 = 500;
+
+/// The states -max..-1, 1..max in order.
+fn states(max: i64) -> impl Iterator<Item = i64> {
+    (-max..=max).filter(|&x| x != 0)
+}
+
+fn state_index(x: i64, max: i64) -> Option<usize> {
+    match x {
+        0 => None,
+        x if x.abs() > max => None,
+        x if x < 0 => Some((x + max) as usize),
+        x => Some((x + max - 1) as usize),
+    }
+}
+
+/// Streaming state of the NIST SP 800-22 random excursions test and its variant.
+///
+/// Both follow the random walk of the +1/-1 mapped bits and split it into cycles between
+/// returns to zero. The test looks at how many times each cycle visits the states -4..4,
+/// the variant at the total number of visits to the states -9..9.
+pub struct RandomExcursions {
+    variant: bool,
+    state: Option<i64>,
+    bits: u64,
+    sum: i64,
+    zeros: u64,
+    // Visits of the current cycle to the states -4..-1, 1..4
+    cycle_visits: [u64; 8],
+    // Cycles per state visiting it 0, 1, 2, 3, 4 and 5 or more times
+    cycles: [[u64; 6]; 8],
+    // Visits of the whole walk to the states -9..-1, 1..9
+    visits: [u64; 18],
+}
+
+impl RandomExcursions {
+    /// Random excursions test, or its variant if `variant` is set. Reports the given state only, or the most
+    /// extreme of all states if `None`.
+    pub fn new(variant: bool, state: Option<i64>) -> Self {
+        RandomExcursions {
+            variant,
+            state,
+            bits: 0,
+            sum: 0,
+            zeros: 0,
+            cycle_visits: [0; 8],
+            cycles: [[0; 6]; 8],
+            visits: [0; 18],
+        }
+    }
+
+    /// Largest |x| of the states this test covers.
+    pub fn max_state(&self) -> i64 {
+        if self.variant { VARIANT_STATES } else { EXCURSION_STATES }
+    }
+
+    fn end_cycle(&mut self) {
+        for (cycles, visits) in self.cycles.iter_mut().zip(self.cycle_visits.iter_mut()) {
+            cycles[(*visits).min(5) as usize] += 1;
+            *visits = 0;
+        }
+    }
+
+    /// Results per state, all skipped if the walk has fewer cycles than NIST requires.
+    /// Closes the last cycle, so no more samples should be fed afterwards.
+    pub fn results(&mut self) -> Vec<(i64, TestResult)> {
+        let max = self.max_state();
+
+        // The walk is padded with a final zero, closing the last cycle
+        if self.sum != 0 {
+            self.end_cycle();
+            self.zeros += 1;
+            self.sum = 0;
+        }
+        let j = self.zeros;
+
+        let minimum = MIN_CYCLES.max((0.005 * (self.bits as f64).sqrt()).ceil() as u64);
+        if j < minimum {
+            return states(max).map(|x| (x, (-1.0, 0.0, 1.0))).collect(); // Not enough cycles
+        }
+
+        let j = j as f64;
+        states(max)
+            .enumerate()
+            .map(|(i, x)| (x, if self.variant { self.variant_result(i, x, j) } else { self.excursion_result(i, x, j) }))
+            .collect()
+    }
+
+    fn excursion_result(&self, i: usize, x: i64, j: f64) -> TestResult {
+        // Probabilities that a cycle visits x exactly 0, 1, ... 4 and 5 or more times
+        let q = 1.0 / (2.0 * x.abs() as f64);
+        let mut probabilities = [0.0; 6];
+        probabilities[0] = 1.0 - q;
+        for (k, p) in probabilities.iter_mut().enumerate().take(5).skip(1) {
+            *p = q * q * (1.0 - q).powi(k as i32 - 1);
+        }
+        probabilities[5] = q * (1.0 - q).powi(4);
+
+        let chi_square: f64 = self.cycles[i]
+            .iter()
+            .zip(probabilities.iter())
+            .map(|(&observed, &p)| (observed as f64 - j * p).powi(2) / (j * p))
+            .sum();
+
+        // chi^2 has 5 degrees of freedom
+        let z_score = (chi_square - 5.0) / 10f64.sqrt();
+        (chi_square, z_score, gamma_ur(2.5, chi_square / 2.0))
+    }
+
+    fn variant_result(&self, i: usize, x: i64, j: f64) -> TestResult {
+        // The visits to x over J cycles have mean J and variance J(4|x| - 2)
+        let visits = self.visits[i] as f64;
+        let z_score = (visits - j) / (j * (4.0 * x.abs() as f64 - 2.0)).sqrt();
+        let normal_dist = Normal::new(0.0, 1.0).expect("Failed to create Normal distribution");
+        (visits, z_score, 2.0 * (1.0 - normal_dist.cdf(z_score.abs())))
+    }
+}
+
+impl Accumulator for RandomExcursions {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                self.sum += 2 * ((byte >> shift) & 1) as i64 - 1;
+                if self.sum == 0 {
+                    self.zeros += 1;
+                    if !self.variant {
+                        self.end_cycle();
+                    }
+                } else if self.variant {
+                    if let Some(i) = state_index(self.sum, VARIANT_STATES) {
+                        self.visits[i] += 1;
+                    }
+                } else if let Some(i) = state_index(self.sum, EXCURSION_STATES) {
+                    self.cycle_visits[i] += 1;
+                }
+            }
+        }
+        self.bits += 8 * chunk.len() as u64;
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+        let results = self.results();
+        match self.state {
+            Some(x) => results.into_iter().find(|&(state, _)| state == x).map_or((-1.0, 0.0, 1.0), |(_, result)| result),
+            None => most_extreme(&results),
+        }
+    }
+}
+
+/// The result of the state with the smallest p-value, with the p-value Sidak corrected for the number of states.
+fn most_extreme(results: &[(i64, TestResult)]) -> TestResult {
+    let (_, (statistic, z_score, p_value)) = results
+        .iter()
+        .copied()
+        .min_by(|a, b| a.1.2.total_cmp(&b.1.2))
+        .unwrap();
+    if statistic == -1.0 {
+        return (-1.0, 0.0, 1.0); // Not enough cycles
+    }
+    (statistic, z_score, 1.0 - (1.0 - p_value).powi(results.len() as i32))
+}
+
+impl Onod {
+
+    /// Random excursions test (NIST SP 800-22, 2.14) for each of the states -4..-1, 1..4.
+    /// Compares how many times the cycles of the random walk of the +1/-1 mapped bits visit each state to random
+    /// data. All results are skipped if the walk returns to zero fewer than 500 times.
+    pub fn random_excursions_states(samples: &[u8]) -> Vec<(i64, TestResult)> {
+        let mut random_excursions = RandomExcursions::new(false, None);
+        random_excursions.update(samples);
+        random_excursions.results()
+    }
+
+    /// Random excursions variant test (NIST SP 800-22, 2.15) for each of the states -9..-1, 1..9.
+    /// Compares the total number of visits of the random walk to each state with the number of cycles.
+    /// All results are skipped if the walk returns to zero fewer than 500 times.
+    pub fn random_excursions_variant_states(samples: &[u8]) -> Vec<(i64, TestResult)> {
+        let mut random_excursions = RandomExcursions::new(true, None);
+        random_excursions.update(samples);
+        random_excursions.results()
+    }
+
+    /// Random excursions test over all states, reporting the most extreme one.
+    pub fn random_excursions(samples: &[u8]) -> (f64, f64, f64) {
+        most_extreme(&Onod::random_excursions_states(samples))
+    }
+
+    /// Random excursions variant test over all states, reporting the most extreme one.
+    pub fn random_excursions_variant(samples: &[u8]) -> (f64, f64, f64) {
+        most_extreme(&Onod::random_excursions_variant_states(samples))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::E;
+
+    fn assert_states(results: &[(i64, TestResult)], expected: &[(i64, f64, f64)]) {
+        for &(x, statistic, p_value) in expected {
+            let (_, (s, _, p)) = results.iter().find(|&&(state, _)| state == x).unwrap();
+            assert!((s - statistic).abs() < 1e-6 && (p - p_value).abs() < 1e-6, "x = {}: {} {}", x, s, p);
+        }
+    }
+
+    #[test]
+    fn matches_nist_examples() {
+        // Section 2.14.8: the walk over the 1,000,000 bits of e has J = 1490 cycles
+        let mut random_excursions = RandomExcursions::new(false, None);
+        random_excursions.update(E);
+        let results = random_excursions.results();
+        assert_eq!(random_excursions.zeros, 1490);
+        assert_states(&results, &[
+            (-4, 3.835698, 0.573306),
+            (-3, 7.318707, 0.197996),
+            (-2, 7.861927, 0.164011),
+            (-1, 15.692617, 0.007779),
+        ]);
+
+        // Section 2.15.8
+        assert_states(&Onod::random_excursions_variant_states(E), &[
+            (-9, 1450.0, 0.858946),
+            (-8, 1435.0, 0.794755),
+            (-7, 1380.0, 0.576249),
+            (-6, 1366.0, 0.493417),
+            (-5, 1412.0, 0.633873),
+            (-4, 1475.0, 0.917283),
+            (-3, 1480.0, 0.934708),
+            (-2, 1468.0, 0.816012),
+            (-1, 1502.0, 0.826009),
+        ]);
+    }
+
+    #[test]
+    fn skips_walks_with_fewer_than_500_cycles() {
+        // The first 100,000 bits of e return to zero fewer than 500 times
+        let mut random_excursions = RandomExcursions::new(false, Some(1));
+        random_excursions.update(&E[..12_500]);
+        assert_eq!(random_excursions.finish(), (-1.0, 0.0, 1.0));
+        assert!(random_excursions.zeros < MIN_CYCLES);
+
+        let samples = &E[..12_500];
+        for results in [Onod::random_excursions_states(samples), Onod::random_excursions_variant_states(samples)] {
+            assert!(results.iter().all(|&(_, result)| result == (-1.0, 0.0, 1.0)));
+        }
+        assert_eq!(Onod::random_excursions(samples), (-1.0, 0.0, 1.0));
+        assert_eq!(Onod::random_excursions_variant(samples), (-1.0, 0.0, 1.0));
+    }
+}
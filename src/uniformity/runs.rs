@@ -26,48 +26,91 @@
 use statrs::distribution::{Normal, ContinuousCDF};
 
 use crate::Onod;
+use crate::stream::Accumulator;
+
+/// Streaming state of the runs test.
+///
+/// The median is only known at the end, so instead of the dichotomous sequence the state keeps
+/// the byte histogram, the counts of every adjacent byte pair and, for every value, how often a
+/// run of that value separates two bytes lying on opposite sides of it. A pair crosses a
+/// half-integer median exactly when it straddles it; for an integer median the samples equal to
+/// it are purged, so the crossings over runs of the median value have to be added.
+pub struct Runs {
+    counts: [u64; 256],
+    pairs: Vec<u64>,
+    crossings: [u64; 256],
+    previous: Option<u8>,
+    before_run: Option<u8>,
+}
 
-impl Onod {
+impl Default for Runs {
+    fn default() -> Self {
+        Runs {
+            counts: [0; 256],
+            pairs: vec![0; 256 * 256],
+            crossings: [0; 256],
+            previous: None,
+            before_run: None,
+        }
+    }
+}
 
-    /// Runs randomness test
-    /// Evaluates the randomness by counting both increasing and decreasing runs and returns a p-value.
-    pub fn runs(samples: &[u8]) -> (f64, f64, f64) {
+impl Accumulator for Runs {
+    fn update(&mut self, chunk: &[u8]) {
+        for &sample in chunk {
+            self.counts[sample as usize] += 1;
+            if let Some(previous) = self.previous {
+                self.pairs[(previous as usize) << 8 | sample as usize] += 1;
+                if sample != previous {
+                    // A run of `previous` ends here
+                    if let Some(before) = self.before_run {
+                        if (before < previous) != (sample < previous) {
+                            self.crossings[previous as usize] += 1;
+                        }
+                    }
+                    self.before_run = Some(previous);
+                }
+            }
+            self.previous = Some(sample);
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
 
-        if samples.is_empty() {
+        let len: u64 = self.counts.iter().sum();
+        if len == 0 {
             return (-1.0, 0.0, 1.0);
         }
 
-        // Clone the samples to avoid modifying the original input
-        let samples = samples.to_vec();
-        let median = calculate_median(&samples);
-
-        let mut above = 0;
-        let mut below = 0;
-
-        // Transform the data into a dichotomous vector and count above/below values
-        let mut purged_samples = Vec::new();
-        for &sample in &samples {
-            if (sample as f64) > median {
-                purged_samples.push(1); // Mark as above
-                above += 1;
-            } else if (sample as f64) < median {
-                purged_samples.push(0); // Mark as below
-                below += 1;
+        let median = calculate_median(&self.counts, len);
+
+        // Count above/below values
+        let mut above = 0.0;
+        let mut below = 0.0;
+        for (value, &count) in self.counts.iter().enumerate() {
+            if (value as f64) > median {
+                above += count as f64; // Mark as above
+            } else if (value as f64) < median {
+                below += count as f64; // Mark as below
             }
         }
 
         // Count runs
         let mut runs_observed = 1; // At least one run exists
-        for window in purged_samples.windows(2) {
-            if window[0] != window[1] {
-                runs_observed += 1;
+        for (pair, &count) in self.pairs.iter().enumerate() {
+            let (a, b) = ((pair >> 8) as f64, (pair & 0xff) as f64);
+            if a.min(b) < median && median < a.max(b) {
+                runs_observed += count;
             }
         }
+        if median.fract() == 0.0 {
+            runs_observed += self.crossings[median as usize];
+        }
 
         // Calculate expected runs and standard deviation
-        let runs_expected = ((2.0 * above as f64 * below as f64) / (above + below) as f64) + 1.0;
-        let std_dev = ((2.0 * above as f64 * below as f64 * (2.0 * above as f64 * below as f64 - above as f64 - below as f64))
-            / (((above + below) as f64).powi(2) * (above + below - 1) as f64))
+        let runs_expected = ((2.0 * above * below) / (above + below)) + 1.0;
+        let std_dev = ((2.0 * above * below * (2.0 * above * below - above - below))
+            / ((above + below).powi(2) * (above + below - 1.0)))
             .sqrt();
 
         // Calculate Z-score
@@ -85,15 +128,45 @@ impl Onod {
     }
 }
 
-/// Helper function to calculate the median of a dataset
-fn calculate_median(samples: &[u8]) -> f64 {
-    let mut sorted_samples = samples.to_vec();
-    sorted_samples.sort_unstable();
+impl Onod {
 
-    let len = sorted_samples.len();
-    if len % 2 == 0 {
-        (sorted_samples[len / 2 - 1] as f64 + sorted_samples[len / 2] as f64) / 2.0
+    /// Runs randomness test
+    /// Evaluates the randomness by counting both increasing and decreasing runs and returns a p-value.
+    pub fn runs(samples: &[u8]) -> (f64, f64, f64) {
+        let mut runs = Runs::default();
+        runs.update(samples);
+        runs.finish()
+    }
+}
+
+/// Helper function to calculate the median of a dataset from its histogram
+fn calculate_median(counts: &[u64; 256], len: u64) -> f64 {
+    // Value at the given position of the sorted samples
+    let nth = |n: u64| {
+        let mut seen = 0;
+        for (value, &count) in counts.iter().enumerate() {
+            seen += count;
+            if seen > n {
+                return value as f64;
+            }
+        }
+        255.0
+    };
+
+    if len.is_multiple_of(2) {
+        (nth(len / 2 - 1) + nth(len / 2)) / 2.0
     } else {
-        sorted_samples[len / 2] as f64
+        nth(len / 2)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::assert_chunking_invariant;
+
+    #[test]
+    fn chunked_input_matches_one_shot() {
+        assert_chunking_invariant(Runs::default);
+    }
+}
@@ -0,0 +1,326 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use crate::Onod;
+use crate::chisquaretest::igamc;
+use crate::stream::Accumulator;
+
+/// Longest pattern length m of the serial test, NIST's default.
+pub const MAX_SERIAL_LENGTH: usize = 16;
+
+/// Longest pattern length m of the approximate entropy test, NIST's default.
+pub const MAX_APPROXIMATE_ENTROPY_LENGTH: usize = 10;
+
+/// Counts of the overlapping patterns of a fixed length in the bit sequence read as a cycle,
+/// i.e. with its first bits appended to its end as NIST SP 800-22 specifies.
+///
+/// The counts of shorter patterns follow by summing over the trailing bits, since every
+/// position of a cycle starts exactly one pattern of each length.
+struct CyclicPatterns {
+    length: usize,
+    counts: Vec<u64>,
+    window: usize,
+    bits: u64,
+    // The first length - 1 bits, most significant first
+    head: usize,
+}
+
+impl CyclicPatterns {
+    fn new(length: usize) -> Self {
+        CyclicPatterns { length, counts: vec![0; 1 << length], window: 0, bits: 0, head: 0 }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        self.window = ((self.window << 1) | bit as usize) & ((1 << self.length) - 1);
+        self.bits += 1;
+        if self.bits < self.length as u64 {
+            self.head = self.window;
+        } else {
+            self.counts[self.window] += 1;
+        }
+    }
+
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                self.push_bit((byte >> shift) & 1 == 1);
+            }
+        }
+    }
+
+    /// Closes the cycle. Returns false if the sequence is shorter than a pattern.
+    fn close(&mut self) -> bool {
+        let n = self.bits;
+        if n < self.length as u64 {
+            return false;
+        }
+        for shift in (0..self.length - 1).rev() {
+            self.push_bit((self.head >> shift) & 1 == 1);
+        }
+        self.bits = n;
+        true
+    }
+
+    /// Counts of the patterns of `m` bits, at most the tracked length.
+    fn counts(&self, m: usize) -> Vec<u64> {
+        let mut counts = vec![0; 1 << m];
+        for (pattern, &count) in self.counts.iter().enumerate() {
+            counts[pattern >> (self.length - m)] += count;
+        }
+        counts
+    }
+
+    /// psi^2_m = 2^m / n * sum(count^2) - n
+    fn psi_square(&self, m: usize) -> f64 {
+        if m == 0 {
+            return 0.0;
+        }
+        let n = self.bits as f64;
+        let sum: f64 = self.counts(m).iter().map(|&count| (count as f64).powi(2)).sum();
+        2f64.powi(m as i32) / n * sum - n
+    }
+
+    /// phi^m = sum(pi_i * ln(pi_i)) with pi_i the relative frequencies of the patterns of `m` bits.
+    fn phi(&self, m: usize) -> f64 {
+        let n = self.bits as f64;
+        self.counts(m)
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| count as f64 / n * (count as f64 / n).ln())
+            .sum()
+    }
+}
+
+/// Largest pattern length NIST recommends for `bits` bits: `floor(log2(bits)) - margin`, capped at `max`.
+fn recommended_length(bits: u64, margin: usize, max: usize) -> Option<usize> {
+    (bits.max(1).ilog2() as usize).checked_sub(margin).map(|m| m.min(max))
+}
+
+/// Streaming state of the NIST SP 800-22 serial test.
+pub struct Serial {
+    m: Option<usize>,
+    difference: u32,
+    patterns: CyclicPatterns,
+}
+
+impl Serial {
+    /// Uses patterns of `m` bits (2 to 16), or the longest NIST recommends for the input length if `None`,
+    /// and reports the first (nabla psi^2_m) or second (nabla^2 psi^2_m) `difference`.
+    pub fn new(m: Option<usize>, difference: u32) -> Self {
+        assert!(difference == 1 || difference == 2, "The serial test has a first and a second difference.");
+        Serial { m, difference, patterns: CyclicPatterns::new(m.unwrap_or(MAX_SERIAL_LENGTH)) }
+    }
+
+    /// Both differences with their p-values.
+    fn results(&mut self) -> [(f64, f64, f64); 2] {
+        let skip = [(-1.0, 0.0, 1.0); 2];
+        if !self.patterns.close() {
+            return skip;
+        }
+
+        // m < floor(log2 n) - 2
+        let m = match self.m.or_else(|| recommended_length(self.patterns.bits, 3, MAX_SERIAL_LENGTH)) {
+            Some(m) if m >= 2 => m,
+            _ => return skip, // Too few bits for patterns of two bits
+        };
+
+        let psi = [m, m - 1, m - 2].map(|length| self.patterns.psi_square(length));
+        let first = psi[0] - psi[1];
+        let second = psi[0] - 2.0 * psi[1] + psi[2];
+
+        // The differences are chi-square distributed with 2^(m-1) and 2^(m-2) degrees of freedom, and 0 when
+        // the patterns are perfectly balanced apart from rounding
+        [(first, 2f64.powi(m as i32 - 1)), (second, 2f64.powi(m as i32 - 2))].map(|(statistic, k)| {
+            let statistic = statistic.max(0.0);
+            let z_score = (statistic - k) / (2.0 * k).sqrt();
+            (statistic, z_score, igamc(k / 2.0, statistic / 2.0))
+        })
+    }
+}
+
+impl Accumulator for Serial {
+    fn update(&mut self, chunk: &[u8]) {
+        self.patterns.update(chunk);
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+        self.results()[self.difference as usize - 1]
+    }
+}
+
+/// Streaming state of the NIST SP 800-22 approximate entropy test.
+pub struct ApproximateEntropy {
+    m: Option<usize>,
+    patterns: CyclicPatterns,
+}
+
+impl Default for ApproximateEntropy {
+    fn default() -> Self {
+        ApproximateEntropy::new(None)
+    }
+}
+
+impl ApproximateEntropy {
+    /// Compares patterns of `m` and m + 1 bits (m from 1 to 10), with m the longest NIST recommends for the input
+    /// length if `None`.
+    pub fn new(m: Option<usize>) -> Self {
+        ApproximateEntropy { m, patterns: CyclicPatterns::new(m.unwrap_or(MAX_APPROXIMATE_ENTROPY_LENGTH) + 1) }
+    }
+}
+
+impl Accumulator for ApproximateEntropy {
+    fn update(&mut self, chunk: &[u8]) {
+        self.patterns.update(chunk);
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if !self.patterns.close() {
+            return (-1.0, 0.0, 1.0); // Fewer bits than a pattern
+        }
+
+        // m < floor(log2 n) - 5
+        let m = match self.m.or_else(|| recommended_length(self.patterns.bits, 6, MAX_APPROXIMATE_ENTROPY_LENGTH)) {
+            Some(m) if m >= 1 => m,
+            _ => return (-1.0, 0.0, 1.0), // Too few bits
+        };
+
+        let n = self.patterns.bits as f64;
+        let approximate_entropy = self.patterns.phi(m) - self.patterns.phi(m + 1);
+
+        // chi^2 = 2n(ln 2 - ApEn) has 2^m degrees of freedom, ApEn can exceed ln 2 by rounding
+        let chi_square = (2.0 * n * (2f64.ln() - approximate_entropy)).max(0.0);
+        let k = 2f64.powi(m as i32);
+        let z_score = (chi_square - k) / (2.0 * k).sqrt();
+        let p_value = igamc(k / 2.0, chi_square / 2.0);
+
+        (approximate_entropy, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Serial test (NIST SP 800-22, 2.11)
+    /// Checks the frequencies of all overlapping m-bit patterns, read cyclically, against those of shorter patterns.
+    /// Returns the first and the second difference of the psi^2 statistics with their p-values.
+    pub fn serial(samples: &[u8]) -> ((f64, f64, f64), (f64, f64, f64)) {
+        let mut serial = Serial::new(None, 1);
+        serial.update(samples);
+        let [first, second] = serial.results();
+        (first, second)
+    }
+
+    /// Approximate entropy test (NIST SP 800-22, 2.12)
+    /// Compares the frequencies of overlapping m-bit and (m+1)-bit patterns, read cyclically, and returns a p-value.
+    pub fn approximate_entropy(samples: &[u8]) -> (f64, f64, f64) {
+        let mut approximate_entropy = ApproximateEntropy::default();
+        approximate_entropy.update(samples);
+        approximate_entropy.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::{E, assert_chunking_invariant, xorshift_bytes};
+
+    fn assert_valid(test: &str, samples: &[u8]) {
+        let (_, _, p_value) = Onod::run(test, samples);
+        assert!((0.0..=1.0).contains(&p_value), "{}: p = {}", test, p_value);
+    }
+
+    #[test]
+    fn balanced_patterns_pass() {
+        // Patterns balanced exactly or up to rounding give statistics of 0 or just below
+        let counter: Vec<u8> = (0..=255).collect();
+        assert_valid("serial", &counter[..32]);
+        assert_valid("serial2", &[0x3a, 0xc1, 0x5e, 0x92, 0x07]);
+        assert_valid("approximate_entropy", &counter);
+        assert_valid("approximate_entropy", &[0x33; 16]);
+        assert_eq!(Onod::run("approximate_entropy", &[0x33; 16]).2, 1.0);
+    }
+
+    fn push_bits(patterns: &mut CyclicPatterns, epsilon: &str) {
+        for bit in epsilon.chars() {
+            patterns.push_bit(bit == '1');
+        }
+    }
+
+    #[test]
+    fn serial_matches_nist_examples() {
+        // SP 800-22 2.11.4: nabla psi^2_3 = 1.6 and nabla^2 psi^2_3 = 0.8
+        let mut serial = Serial::new(Some(3), 1);
+        push_bits(&mut serial.patterns, "0011011101");
+        let [(first, _, p1), (second, _, p2)] = serial.results();
+        assert!((first - 1.6).abs() < 1e-9 && (second - 0.8).abs() < 1e-9, "{} {}", first, second);
+        assert!((p1 - 0.808792).abs() < 1e-6, "p1 = {}", p1);
+        assert!((p2 - 0.670320).abs() < 1e-6, "p2 = {}", p2);
+
+        // SP 800-22 2.11.8: m = 2 on the first million bits of e
+        let mut serial = Serial::new(Some(2), 1);
+        serial.update(E);
+        let [(_, _, p1), (_, _, p2)] = serial.results();
+        assert!((p1 - 0.843764).abs() < 1e-6, "p1 = {}", p1);
+        assert!((p2 - 0.561915).abs() < 1e-6, "p2 = {}", p2);
+    }
+
+    #[test]
+    fn approximate_entropy_matches_nist_examples() {
+        // SP 800-22 2.12.4: ApEn(3) = 0.190954
+        let mut approximate_entropy = ApproximateEntropy::new(Some(3));
+        push_bits(&mut approximate_entropy.patterns, "0100110101");
+        let (apen, _, p) = approximate_entropy.finish();
+        assert!((apen - 0.190954).abs() < 1e-6, "ApEn = {}", apen);
+        assert!((p - 0.261961).abs() < 1e-6, "p = {}", p);
+
+        // SP 800-22 2.12.8: ApEn(2) = 0.665393 on 100 bits of e
+        let mut approximate_entropy = ApproximateEntropy::new(Some(2));
+        push_bits(
+            &mut approximate_entropy.patterns,
+            "1100100100001111110110101010001000100001011010001100001000110100110001001100011001100010100010111000",
+        );
+        let (apen, _, p) = approximate_entropy.finish();
+        assert!((apen - 0.665393).abs() < 1e-6, "ApEn = {}", apen);
+        assert!((p - 0.235301).abs() < 1e-6, "p = {}", p);
+    }
+
+    #[test]
+    fn reads_bytes_most_significant_bit_first() {
+        let samples = xorshift_bytes(97);
+        let mut bitwise = ApproximateEntropy::new(Some(4));
+        for byte in &samples {
+            push_bits(&mut bitwise.patterns, &format!("{:08b}", byte));
+        }
+        let mut bytewise = ApproximateEntropy::new(Some(4));
+        bytewise.update(&samples);
+        assert_eq!(bitwise.finish(), bytewise.finish());
+    }
+
+    #[test]
+    fn chunked_input_matches_one_shot() {
+        assert_chunking_invariant(|| Serial::new(None, 2));
+        assert_chunking_invariant(ApproximateEntropy::default);
+    }
+}
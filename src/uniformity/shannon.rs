@@ -26,25 +26,36 @@
 use statrs::distribution::{Normal, ContinuousCDF};
 
 use crate::Onod;
+use crate::stream::Accumulator;
 
-impl Onod {
-    /// Entropy randomness test
-    /// Calculates the Shannon entropy of a byte slice and outputs a p-value.
-    pub fn shannon(samples: &[u8]) -> (f64, f64, f64) {
+/// Streaming state of the Shannon entropy test.
+pub struct Shannon {
+    counts: [u64; 256],
+}
 
-        let len = samples.len() as f64;
-        if len == 0.0 {
-            return (-1.0, 0.0, 1.0);
-        }
+impl Default for Shannon {
+    fn default() -> Self {
+        Shannon { counts: [0; 256] }
+    }
+}
 
+impl Accumulator for Shannon {
+    fn update(&mut self, chunk: &[u8]) {
         // Count occurrences of each byte
-        let mut counts = [0usize; 256];
-        for &byte in samples {
-            counts[byte as usize] += 1;
+        for &byte in chunk {
+            self.counts[byte as usize] += 1;
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        let len = self.counts.iter().sum::<u64>() as f64;
+        if len == 0.0 {
+            return (-1.0, 0.0, 1.0);
         }
 
         // Calculate Shannon entropy
-        let entropy: f64 = counts.iter()
+        let entropy: f64 = self.counts.iter()
             .filter(|&&count| count > 0)
             .map(|&count| {
                 let p = count as f64 / len;
@@ -66,3 +77,13 @@ impl Onod {
         (entropy, z_score, p_value)
     }
 }
+
+impl Onod {
+    /// Entropy randomness test
+    /// Calculates the Shannon entropy of a byte slice and outputs a p-value.
+    pub fn shannon(samples: &[u8]) -> (f64, f64, f64) {
+        let mut shannon = Shannon::default();
+        shannon.update(samples);
+        shannon.finish()
+    }
+}
@@ -0,0 +1,315 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::function::gamma::gamma_ur;
+
+use crate::Onod;
+use crate::chisquaretest::chi_square_statistic;
+use crate::stream::{Accumulator, TestResult};
+
+/// Template length m used when none is given, as recommended by NIST SP 800-22.
+pub const TEMPLATE_LENGTH: usize = 9;
+
+/// Longest supported template, bounding the size of the template lookup table.
+pub const MAX_TEMPLATE_LENGTH: usize = 21;
+
+/// Number of blocks N of the non-overlapping template matching test.
+pub const NON_OVERLAPPING_BLOCKS: usize = 8;
+
+/// Block size M of the overlapping template matching test.
+pub const OVERLAPPING_BLOCK_SIZE: usize = 1032;
+
+// Blocks are classified by 0, 1, 2, 3, 4 and 5 or more occurrences of the template
+const OVERLAPPING_CLASSES: usize = 6;
+
+/// All aperiodic templates of `m` bits in ascending order, the default templates of the
+/// non-overlapping test (148 for m = 9). Templates are read most significant bit first.
+///
+/// A template is aperiodic if no proper prefix equals the suffix of the same length,
+/// so two of its occurrences can never overlap.
+pub fn aperiodic_templates(m: usize) -> Vec<u64> {
+    (0..1u64 << m)
+        .filter(|&template| (1..m).all(|length| template >> (m - length) != template & ((1 << length) - 1)))
+        .collect()
+}
+
+fn bits(samples: &[u8]) -> Vec<u8> {
+    samples.iter().flat_map(|&byte| (0..8).rev().map(move |shift| (byte >> shift) & 1)).collect()
+}
+
+/// Streaming state of the NIST SP 800-22 overlapping template matching test.
+pub struct OverlappingTemplate {
+    template: u64,
+    m: usize,
+    block_size: usize,
+    window: u64,
+    bits: usize,
+    matches: usize,
+    observed: [u64; OVERLAPPING_CLASSES],
+}
+
+impl Default for OverlappingTemplate {
+    fn default() -> Self {
+        OverlappingTemplate::new((1 << TEMPLATE_LENGTH) - 1, TEMPLATE_LENGTH, OVERLAPPING_BLOCK_SIZE)
+    }
+}
+
+impl OverlappingTemplate {
+    /// Counts the overlapping occurrences of the `m`-bit `template` in blocks of `block_size` bits.
+    pub fn new(template: u64, m: usize, block_size: usize) -> Self {
+        assert!((1..=MAX_TEMPLATE_LENGTH).contains(&m) && template >> m == 0, "Template does not fit in m bits.");
+        OverlappingTemplate { template, m, block_size, window: 0, bits: 0, matches: 0, observed: [0; OVERLAPPING_CLASSES] }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        self.window = ((self.window << 1) | bit as u64) & ((1 << self.m) - 1);
+        self.bits += 1;
+        if self.bits >= self.m && self.window == self.template {
+            self.matches += 1;
+        }
+        if self.bits == self.block_size {
+            self.observed[self.matches.min(OVERLAPPING_CLASSES - 1)] += 1;
+            self.window = 0;
+            self.bits = 0;
+            self.matches = 0;
+        }
+    }
+
+    /// Exact probabilities of 0, 1, ... 4 and 5 or more occurrences in a random block.
+    ///
+    /// Runs the block through the string matching automaton of the template, whose state
+    /// is the length of the longest template prefix the bits seen so far end with.
+    pub fn class_probabilities(&self) -> [f64; OVERLAPPING_CLASSES] {
+        let m = self.m;
+        let bit_at = |i: usize| (self.template >> (m - 1 - i)) & 1;
+
+        // Longest proper prefix of the first `length` template bits that is also their suffix
+        let mut failure = vec![0usize; m + 1];
+        for length in 2..=m {
+            let mut k = failure[length - 1];
+            while k > 0 && bit_at(k) != bit_at(length - 1) {
+                k = failure[k];
+            }
+            if bit_at(k) == bit_at(length - 1) {
+                k += 1;
+            }
+            failure[length] = k;
+        }
+        let next = |mut state: usize, bit: u64| {
+            if state == m {
+                state = failure[m];
+            }
+            while state > 0 && bit_at(state) != bit {
+                state = failure[state];
+            }
+            if bit_at(state) == bit { state + 1 } else { 0 }
+        };
+        let transitions: Vec<[usize; 2]> = (0..=m).map(|state| [next(state, 0), next(state, 1)]).collect();
+
+        let mut probabilities = vec![[0.0; OVERLAPPING_CLASSES]; m + 1];
+        probabilities[0][0] = 1.0;
+        for _ in 0..self.block_size {
+            let mut stepped = vec![[0.0; OVERLAPPING_CLASSES]; m + 1];
+            for (state, classes) in probabilities.iter().enumerate() {
+                for &target in &transitions[state] {
+                    for (class, &p) in classes.iter().enumerate() {
+                        let class = if target == m { (class + 1).min(OVERLAPPING_CLASSES - 1) } else { class };
+                        stepped[target][class] += 0.5 * p;
+                    }
+                }
+            }
+            probabilities = stepped;
+        }
+
+        let mut classes = [0.0; OVERLAPPING_CLASSES];
+        for state in probabilities {
+            for (total, p) in classes.iter_mut().zip(state) {
+                *total += p;
+            }
+        }
+        classes
+    }
+}
+
+impl Accumulator for OverlappingTemplate {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                self.push_bit((byte >> shift) & 1 == 1);
+            }
+        }
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        if self.observed.iter().sum::<u64>() == 0 {
+            return (-1.0, 0.0, 1.0); // Not a single complete block
+        }
+
+        // Classes that cannot occur in blocks this short are left out
+        let (observed, expected): (Vec<u64>, Vec<f64>) = self.observed
+            .iter()
+            .zip(self.class_probabilities())
+            .filter(|&(_, p)| p > 0.0)
+            .map(|(&observed, p)| (observed, p))
+            .unzip();
+        if observed.len() < 2 {
+            return (-1.0, 0.0, 1.0); // The template occurs equally often in every block
+        }
+
+        let chi_square = chi_square_statistic(&observed, &expected);
+        let k = (observed.len() - 1) as f64;
+        let z_score = (chi_square - k) / (2.0 * k).sqrt();
+        let p_value = gamma_ur(k / 2.0, chi_square / 2.0);
+
+        (chi_square, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Non-overlapping template matching test (NIST SP 800-22, 2.7) for each of the given `m`-bit templates.
+    /// Splits the bits into `blocks` blocks and compares the number of non-overlapping occurrences of a template
+    /// per block to its expectation. Returns one result per template; the templates must be distinct.
+    pub fn non_overlapping_templates(samples: &[u8], templates: &[u64], m: usize, blocks: usize) -> Vec<TestResult> {
+        assert!((1..=MAX_TEMPLATE_LENGTH).contains(&m), "Template length must be between 1 and {}.", MAX_TEMPLATE_LENGTH);
+        non_overlapping_templates(&bits(samples), templates, m, blocks)
+    }
+
+    /// Non-overlapping template matching test over all 148 aperiodic 9-bit templates in 8 blocks.
+    /// Returns the proportion of templates passing, its z-score and the second-level p-value of the uniformity of
+    /// the per-template p-values.
+    pub fn non_overlapping_template(samples: &[u8]) -> (f64, f64, f64) {
+        let templates = aperiodic_templates(TEMPLATE_LENGTH);
+        let results = Onod::non_overlapping_templates(samples, &templates, TEMPLATE_LENGTH, NON_OVERLAPPING_BLOCKS);
+        Onod::second_level(&results)
+    }
+
+    /// Overlapping template matching test (NIST SP 800-22, 2.8)
+    /// Compares how often blocks of 1032 bits hold 0 to 5 or more overlapping runs of nine 1s to random data
+    /// and returns a p-value.
+    pub fn overlapping_template(samples: &[u8]) -> (f64, f64, f64) {
+        let mut overlapping_template = OverlappingTemplate::default();
+        overlapping_template.update(samples);
+        overlapping_template.finish()
+    }
+}
+
+/// Non-overlapping template matching test on `bits`, one bit per byte.
+fn non_overlapping_templates(bits: &[u8], templates: &[u64], m: usize, blocks: usize) -> Vec<TestResult> {
+    let block_size = bits.len() / blocks.max(1);
+    if blocks == 0 || block_size < m {
+        return vec![(-1.0, 0.0, 1.0); templates.len()]; // Blocks too short to hold a template
+    }
+
+    // Templates by their value, so every window is looked up once whatever the number of templates
+    let mut index = vec![u32::MAX; 1 << m];
+    for (i, &template) in templates.iter().enumerate() {
+        assert!(template >> m == 0, "Template {:b} does not fit in {} bits.", template, m);
+        assert!(index[template as usize] == u32::MAX, "Template {:b} is given twice.", template);
+        index[template as usize] = i as u32;
+    }
+
+    // W_j: occurrences of each template in block j, matching restarts after the end of an occurrence
+    let mut counts = vec![vec![0u64; blocks]; templates.len()];
+    for (j, block) in bits.chunks_exact(block_size).take(blocks).enumerate() {
+        let mut next_start = vec![0usize; templates.len()];
+        let mut window = 0usize;
+        for (i, &bit) in block.iter().enumerate() {
+            window = ((window << 1) | bit as usize) & ((1 << m) - 1);
+            if i + 1 < m {
+                continue;
+            }
+            let start = i + 1 - m;
+            let t = index[window];
+            if t != u32::MAX && start >= next_start[t as usize] {
+                counts[t as usize][j] += 1;
+                next_start[t as usize] = start + m;
+            }
+        }
+    }
+
+    // Mean and variance of W_j for random data
+    let (big_m, two_m) = (block_size as f64, 2f64.powi(m as i32));
+    let mean = (big_m - m as f64 + 1.0) / two_m;
+    let variance = big_m * (1.0 / two_m - (2.0 * m as f64 - 1.0) / (two_m * two_m));
+
+    let n = blocks as f64;
+    counts
+        .iter()
+        .map(|counts| {
+            let chi_square: f64 = counts.iter().map(|&w| (w as f64 - mean).powi(2) / variance).sum();
+            let z_score = (chi_square - n) / (2.0 * n).sqrt();
+            (chi_square, z_score, gamma_ur(n / 2.0, chi_square / 2.0))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::E;
+
+    #[test]
+    fn non_overlapping_matches_nist_examples() {
+        // SP 800-22 2.7.4: template 001 in two blocks of ten bits
+        let epsilon = [1, 0, 1, 0, 0, 1, 0, 0, 1, 0, 1, 1, 1, 0, 0, 1, 0, 1, 1, 0];
+        let (chi_square, _, p) = non_overlapping_templates(&epsilon, &[0b001], 3, 2)[0];
+        assert!((chi_square - 2.133333).abs() < 1e-6, "chi_square = {}", chi_square);
+        assert!((p - 0.344154).abs() < 1e-6, "p = {}", p);
+
+        // SP 800-22 Appendix B: template 000000001 on the first million bits of e
+        let (_, _, p) = Onod::non_overlapping_templates(E, &[1], TEMPLATE_LENGTH, NON_OVERLAPPING_BLOCKS)[0];
+        assert!((p - 0.078790).abs() < 1e-6, "p = {}", p);
+    }
+
+    #[test]
+    fn class_probabilities_match_nist() {
+        // The corrected probabilities of the NIST reference implementation (sts 2.1.2) for m = 9, M = 1032
+        let expected = [0.364091, 0.185659, 0.139381, 0.100571, 0.070432, 0.139865];
+        let probabilities = OverlappingTemplate::default().class_probabilities();
+        for (p, expected) in probabilities.iter().zip(expected) {
+            assert!((p - expected).abs() < 1e-6, "p = {}", p);
+        }
+    }
+
+    #[test]
+    fn overlapping_matches_nist_example() {
+        // SP 800-22 2.8.8 on the first million bits of e. The document's chi-square of 8.965859 and
+        // P-value of 0.110434 come from the Poisson approximation it used to give for the class probabilities;
+        // with the exact probabilities the same counts give the values below.
+        let mut overlapping_template = OverlappingTemplate::default();
+        overlapping_template.update(E);
+        assert_eq!(overlapping_template.observed, [329, 164, 150, 111, 78, 136]);
+
+        let old = [0.367879, 0.183940, 0.137955, 0.099634, 0.069935, 0.140657];
+        let chi_square = chi_square_statistic(&overlapping_template.observed, &old);
+        assert!((chi_square - 8.965859).abs() < 1e-4, "chi_square = {}", chi_square);
+
+        let (chi_square, _, p) = overlapping_template.finish();
+        assert!((chi_square - 7.949564).abs() < 1e-6, "chi_square = {}", chi_square);
+        assert!((p - 0.159037).abs() < 1e-6, "p = {}", p);
+    }
+}
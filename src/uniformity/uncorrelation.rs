@@ -26,50 +26,57 @@
 use statrs::distribution::{Normal, ContinuousCDF};
 
 use crate::Onod;
+use crate::stream::Accumulator;
+
+/// Streaming state of the uncorrelation test.
+/// The shifted sequence is a rotation of the original one, so both share mean and variance
+/// and only the lag-1 products have to be accumulated.
+#[derive(Default)]
+pub struct UnCorrelation {
+    first: Option<u8>,
+    previous: Option<u8>,
+    len: u64,
+    sum: u128,
+    sum_squares: u128,
+    sum_products: u128,
+}
+
+impl Accumulator for UnCorrelation {
+    fn update(&mut self, chunk: &[u8]) {
+        for &sample in chunk {
+            let sample = sample as u128;
+            if let Some(previous) = self.previous {
+                self.sum_products += previous as u128 * sample;
+            } else {
+                self.first = Some(sample as u8);
+            }
+            self.sum += sample;
+            self.sum_squares += sample * sample;
+            self.len += 1;
+            self.previous = Some(sample as u8);
+        }
+    }
 
-impl Onod {
-
-    /// UnCorrelation randomness test
-    /// Computes the Pearson correlation between the sequence and its shifted version, returning a p-value.
-    pub fn uncorrelation(input: &[u8]) -> (f64, f64, f64) {
-
-        let samples = input.iter().map(|&x| x as i32).collect::<Vec<i32>>();
+    fn finish(&mut self) -> (f64, f64, f64) {
 
-        if samples.len() < 2 {
+        if self.len < 2 {
             return (-1.0, 0.0, 1.0); // Default to perfect randomness for insufficient data
         }
 
-        // Convert samples to f64 for correlation computation
-        let samples_a: Vec<f64> = samples.iter().map(|&x| x as f64).collect();
+        // Wrap around: the shifted sequence starts with the last sample
+        let (first, last) = (self.first.unwrap_or(0) as u128, self.previous.unwrap_or(0) as u128);
+        let sum_products = self.sum_products + first * last;
 
-        // Create a shifted version of the sequence
-        let mut samples_b = vec![0.0; samples.len()];
-        samples_b[0] = samples_a[samples.len() - 1]; // Wrap around
-        for i in 1..samples.len() {
-            samples_b[i] = samples_a[i - 1];
-        }
-
-        // Calculate mean of both sequences
-        let mean_a = samples_a.iter().sum::<f64>() / samples_a.len() as f64;
-        let mean_b = samples_b.iter().sum::<f64>() / samples_b.len() as f64;
-
-        // Compute Pearson correlation coefficient
-        let mut numerator = 0.0;
-        let mut denominator_a = 0.0;
-        let mut denominator_b = 0.0;
-
-        for i in 0..samples.len() {
-            let diff_a = samples_a[i] - mean_a;
-            let diff_b = samples_b[i] - mean_b;
-            numerator += diff_a * diff_b;
-            denominator_a += diff_a.powi(2);
-            denominator_b += diff_b.powi(2);
-        }
+        // Compute Pearson correlation coefficient, scaled by n^2 to stay in integers
+        let len = self.len as i128;
+        let sum = self.sum as i128;
+        let numerator = len * sum_products as i128 - sum * sum;
+        let denominator = len * self.sum_squares as i128 - sum * sum;
 
-        let correlation = numerator / (denominator_a.sqrt() * denominator_b.sqrt());
+        let correlation = numerator as f64 / denominator as f64;
 
         // Calculate p-value for null hypothesis of zero correlation
-        let n = samples.len() as f64;
+        let n = self.len as f64;
         let t_stat = correlation * ((n - 2.0) / (1.0 - correlation.powi(2))).sqrt();
 
         // Use t-distribution approximation for large n
@@ -78,5 +85,27 @@ impl Onod {
 
         (correlation, t_stat, p_value)
     }
+}
 
-}
\ No newline at end of file
+impl Onod {
+
+    /// UnCorrelation randomness test
+    /// Computes the Pearson correlation between the sequence and its shifted version, returning a p-value.
+    pub fn uncorrelation(input: &[u8]) -> (f64, f64, f64) {
+        let mut uncorrelation = UnCorrelation::default();
+        uncorrelation.update(input);
+        uncorrelation.finish()
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::assert_chunking_invariant;
+
+    #[test]
+    fn chunked_input_matches_one_shot() {
+        assert_chunking_invariant(UnCorrelation::default);
+    }
+}
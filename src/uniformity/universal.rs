@@ -0,0 +1,192 @@
+// MIT License
+
+// Copyright (c) 2025 Tobias Raayoni Last (@randogoth)
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+// ---
+
+
+use statrs::distribution::{Normal, ContinuousCDF};
+
+use crate::Onod;
+use crate::stream::Accumulator;
+
+/// Expected value and variance of log2 of the distance between repeated L-bit blocks, for L = 1 to 16.
+const EXPECTED: [(f64, f64); 16] = [
+    (0.7326495, 0.690), (1.5374383, 1.338), (2.4016068, 1.901), (3.3112247, 2.358),
+    (4.2534266, 2.705), (5.2177052, 2.954), (6.1962507, 3.125), (7.1836656, 3.238),
+    (8.1764248, 3.311), (9.1723243, 3.356), (10.170032, 3.384), (11.168765, 3.401),
+    (12.168070, 3.410), (13.167693, 3.416), (14.167488, 3.419), (15.167379, 3.421),
+];
+
+/// Supported block lengths L. Shorter blocks give badly calibrated p-values.
+pub const MIN_BLOCK_LENGTH: usize = 6;
+pub const MAX_BLOCK_LENGTH: usize = EXPECTED.len();
+
+/// Fewest test blocks K for which the p-value is reported.
+const MIN_TEST_BLOCKS: u64 = 1000;
+
+/// Distances between repeated blocks of one length.
+struct Blocks {
+    length: usize,
+    init_blocks: u64,
+    block: usize,
+    bits: usize,
+    // Index, counted from 1, of the latest occurrence of each block value
+    last_seen: Vec<u64>,
+    blocks: u64,
+    sum: f64,
+}
+
+impl Blocks {
+    fn new(length: usize, init_blocks: u64) -> Self {
+        Blocks { length, init_blocks, block: 0, bits: 0, last_seen: vec![0; 1 << length], blocks: 0, sum: 0.0 }
+    }
+
+    fn push_bit(&mut self, bit: bool) {
+        self.block = (self.block << 1) | bit as usize;
+        self.bits += 1;
+        if self.bits < self.length {
+            return;
+        }
+        self.blocks += 1;
+        if self.blocks > self.init_blocks {
+            self.sum += ((self.blocks - self.last_seen[self.block]) as f64).log2();
+        }
+        self.last_seen[self.block] = self.blocks;
+        self.block = 0;
+        self.bits = 0;
+    }
+}
+
+/// Streaming state of Maurer's universal statistical test.
+///
+/// Unless given, the block length is picked from the input length when the test completes,
+/// so all block lengths are tracked side by side.
+pub struct Universal {
+    bits: u64,
+    blocks: Vec<Blocks>,
+}
+
+impl Default for Universal {
+    fn default() -> Self {
+        Universal::new(None, None)
+    }
+}
+
+impl Universal {
+    /// Uses blocks of `block_length` bits (L, 6 to 16) of which the first `init_blocks` (Q, by default 10 * 2^L)
+    /// only initialize the table of last occurrences. Without a block length the largest one for which the input
+    /// holds Q + 1000 * 2^L blocks is used, or 6 for shorter inputs.
+    pub fn new(block_length: Option<usize>, init_blocks: Option<u64>) -> Self {
+        let lengths = match block_length {
+            Some(length) => length..=length,
+            None => MIN_BLOCK_LENGTH..=MAX_BLOCK_LENGTH,
+        };
+        let blocks = lengths
+            .map(|length| Blocks::new(length, init_blocks.unwrap_or(10 << length)))
+            .collect();
+        Universal { bits: 0, blocks }
+    }
+}
+
+impl Accumulator for Universal {
+    fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            for shift in (0..8).rev() {
+                let bit = (byte >> shift) & 1 == 1;
+                for blocks in self.blocks.iter_mut() {
+                    blocks.push_bit(bit);
+                }
+            }
+        }
+        self.bits += 8 * chunk.len() as u64;
+    }
+
+    fn finish(&mut self) -> (f64, f64, f64) {
+
+        // NIST SP 800-22 recommends Q = 10 * 2^L initialization and K = 1000 * 2^L test blocks
+        let blocks = self.blocks
+            .iter()
+            .rev()
+            .find(|b| self.bits >= 1010 * (1 << b.length) * b.length as u64)
+            .unwrap_or(&self.blocks[0]);
+
+        if blocks.blocks < blocks.init_blocks + MIN_TEST_BLOCKS {
+            return (-1.0, 0.0, 1.0); // Too few test blocks after the initialization segment
+        }
+
+        let k = (blocks.blocks - blocks.init_blocks) as f64;
+        let fn_statistic = blocks.sum / k;
+
+        // Standard deviation with the correction factor of Coron and Naccache for finite K
+        let l = blocks.length as f64;
+        let (expected_value, variance) = EXPECTED[blocks.length - 1];
+        let c = 0.7 - 0.8 / l + (4.0 + 32.0 / l) * k.powf(-3.0 / l) / 15.0;
+        let sigma = c * (variance / k).sqrt();
+
+        let z_score = (fn_statistic - expected_value) / sigma;
+        let normal_dist = Normal::new(0.0, 1.0).expect("Failed to create Normal distribution");
+        let p_value = 2.0 * (1.0 - normal_dist.cdf(z_score.abs()));
+
+        (fn_statistic, z_score, p_value)
+    }
+}
+
+impl Onod {
+
+    /// Maurer's universal statistical test (NIST SP 800-22, 2.9)
+    /// Measures the average log2 distance between repeated L-bit blocks, i.e. how far the data could be compressed,
+    /// and returns a p-value.
+    pub fn universal(samples: &[u8]) -> (f64, f64, f64) {
+        let mut universal = Universal::default();
+        universal.update(samples);
+        universal.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::testing::E;
+
+    #[test]
+    fn matches_nist_example() {
+        // Appendix B: the 1,000,000 bits of e with L = 7 and Q = 1280, which leave K = 141,577 test blocks
+        let mut universal = Universal::new(Some(7), Some(1280));
+        universal.update(E);
+        assert_eq!(universal.blocks[0].blocks - universal.blocks[0].init_blocks, 141_577);
+        let result = universal.finish();
+        assert!((result.2 - 0.282568).abs() < 1e-6, "p = {}", result.2);
+
+        // 10^6 bits are too few for L = 8, so the same parameters are picked from the length
+        assert_eq!(Onod::universal(E), result);
+
+        // Section 2.9.8 works on 2^20 bits of the G-SHA-1 generator, which are not at hand: its K = 148,516 test
+        // blocks of L = 7 bits give a sum of 919,924.038020, fn = 6.194107 and sigma = 0.002703
+        let mut blocks = Blocks::new(7, 1280);
+        (blocks.blocks, blocks.sum) = (1280 + 148_516, 919_924.038020);
+        let mut universal = Universal { bits: 1 << 20, blocks: vec![blocks] };
+        let (fn_statistic, z_score, p_value) = universal.finish();
+        assert!((fn_statistic - 6.194107).abs() < 1e-6, "fn = {}", fn_statistic);
+        assert!(((fn_statistic - 6.1962507) / z_score - 0.002703).abs() < 1e-6, "z = {}", z_score);
+        assert!((p_value - 0.427733).abs() < 1e-6, "p = {}", p_value);
+    }
+}
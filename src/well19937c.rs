@@ -54,7 +54,7 @@ impl Well19937c {
             let x = (self.state[i] & UPPER_MASK) + (self.state[(i + 1) % 624] & LOWER_MASK);
             let mut x_a = x >> 1;
 
-            if x % 2 != 0 {
+            if !x.is_multiple_of(2) {
                 x_a ^= MATRIX_A;
             }
 
@@ -80,6 +80,19 @@ impl Well19937c {
         y
     }
 
+    /// Generates a uniformly distributed integer in [0, bound), without modulo bias.
+    pub fn next_below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "The bound must be positive.");
+        // Reject the top partial range of 2^32 mod bound values
+        let limit = u32::MAX - (u32::MAX - bound + 1) % bound;
+        loop {
+            let x = self.next_u32();
+            if x <= limit {
+                return x % bound;
+            }
+        }
+    }
+
     /// Generates the next random `f64` in [0, 1).
     pub fn next_f64(&mut self) -> f64 {
         self.next_u32() as f64 / u32::MAX as f64